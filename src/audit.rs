@@ -0,0 +1,57 @@
+use crate::config::AuditConfig;
+use crate::core::process::Exposure;
+use crate::core::ProcessInfo;
+use serde::Serialize;
+
+#[derive(Debug, Clone, Serialize)]
+pub struct AuditFinding {
+    #[serde(flatten)]
+    pub process: ProcessInfo,
+    pub exposure: Exposure,
+    pub violation: bool,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct AuditReport {
+    pub findings: Vec<AuditFinding>,
+    pub max_exposure: Exposure,
+    pub kinds: Vec<String>,
+}
+
+impl AuditReport {
+    pub fn violations(&self) -> usize {
+        self.findings.iter().filter(|f| f.violation).count()
+    }
+}
+
+// Classifies every listener and flags the ones whose kind is policed by the config but which
+// are reachable beyond `max_exposure`.
+pub fn audit(infos: &[ProcessInfo], policy: &AuditConfig) -> AuditReport {
+    let max_exposure = policy.max_exposure.unwrap_or(Exposure::Loopback);
+    let kinds = policy
+        .kinds
+        .clone()
+        .unwrap_or_else(|| vec!["dev".to_string(), "docker".to_string()]);
+
+    let findings = infos
+        .iter()
+        .map(|info| {
+            let exposure = Exposure::of(&info.bind_addr);
+            let policed = kinds
+                .iter()
+                .any(|k| k.eq_ignore_ascii_case(info.kind.as_str()));
+            let allowed = policy.allow_ports.contains(&info.port);
+            AuditFinding {
+                process: info.clone(),
+                exposure,
+                violation: policed && !allowed && exposure > max_exposure,
+            }
+        })
+        .collect();
+
+    AuditReport {
+        findings,
+        max_exposure,
+        kinds,
+    }
+}
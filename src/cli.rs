@@ -1,3 +1,7 @@
+use crate::core::classify::parse_port_range;
+use crate::core::containers::ContainerAction;
+use crate::core::BackendStrategy;
+use crate::ops::{AddrFilter, ProtocolFilter, SortKey, StackAction};
 use clap::{Parser, Subcommand};
 use std::path::PathBuf;
 
@@ -18,6 +22,10 @@ pub struct Cli {
     /// Verbose output
     #[arg(long)]
     pub verbose: bool,
+
+    /// Port discovery backend (overrides [backend] strategy in config)
+    #[arg(long, global = true, value_enum)]
+    pub backend: Option<BackendStrategy>,
 }
 
 #[derive(Subcommand, Debug)]
@@ -32,6 +40,22 @@ pub enum Commands {
         #[arg(long)]
         to: Option<u16>,
 
+        /// Protocol to include
+        #[arg(long, value_enum, default_value_t = ProtocolFilter::All)]
+        proto: ProtocolFilter,
+
+        /// Bind address to include: loopback, any (0.0.0.0 / ::) or an exact IP
+        #[arg(long)]
+        addr: Option<AddrFilter>,
+
+        /// Show the full command (e.g. `next dev` instead of `node`)
+        #[arg(short, long)]
+        wide: bool,
+
+        /// Sort order
+        #[arg(long, value_enum, default_value_t = SortKey::Port)]
+        sort: SortKey,
+
         /// Output as JSON
         #[arg(long)]
         json: bool,
@@ -52,10 +76,34 @@ pub enum Commands {
         key: String,
     },
 
-    /// Kill process on specified port
+    /// Kill process on specified port, or every listener matching the filters
     Kill {
         /// Target port
-        port: u16,
+        #[arg(
+            required_unless_present_any = ["list_signals", "range", "kind", "project", "cmd", "user"],
+            conflicts_with = "range"
+        )]
+        port: Option<u16>,
+
+        /// Kill every listener in a port range (e.g. 3000-3999)
+        #[arg(long, value_parser = parse_port_range)]
+        range: Option<(u16, u16)>,
+
+        /// Only listeners of this kind (dev, docker, k8s...)
+        #[arg(long)]
+        kind: Option<String>,
+
+        /// Only listeners of this project or compose stack (as shown in the PROJ column)
+        #[arg(long)]
+        project: Option<String>,
+
+        /// Only listeners with this command name (e.g. node)
+        #[arg(long)]
+        cmd: Option<String>,
+
+        /// Only listeners owned by this user
+        #[arg(long)]
+        user: Option<String>,
 
         /// Dry run (don't actually kill)
         #[arg(long)]
@@ -69,13 +117,104 @@ pub enum Commands {
         #[arg(long)]
         all_users: bool,
 
-        /// Signal to send (INT, TERM, KILL)
+        /// Also kill listeners covered by [kill] protect in the config
+        #[arg(long)]
+        override_protection: bool,
+
+        /// Signal to send, by name (HUP, SIGUSR1...) or number
         #[arg(long)]
         signal: Option<String>,
 
+        /// List the signals accepted by --signal
+        #[arg(long, exclusive = true)]
+        list_signals: bool,
+
         /// Force kill (SIGKILL)
         #[arg(long)]
         force: bool,
+
+        /// Signal the listener's whole process group
+        #[arg(long, conflicts_with = "parent")]
+        tree: bool,
+
+        /// Target the supervising parent (npm run dev, cargo watch...) instead of the listener
+        #[arg(long)]
+        parent: bool,
+
+        /// What to do when the port is published by a container (default: stop, or kill with --force)
+        #[arg(long, value_enum)]
+        container: Option<ContainerAction>,
+
+        /// Output one JSON record per process with what was done and how it ended
+        #[arg(long)]
+        json: bool,
+    },
+
+    /// Stop the process on a port and start its command again (same argv, cwd and environment)
+    Restart {
+        /// Port the process listens on
+        port: u16,
+
+        /// Set PORT in its environment to this value before starting it again
+        #[arg(long = "port", value_name = "NEW")]
+        new_port: Option<u16>,
+
+        /// Dry run (don't actually stop or start anything)
+        #[arg(long)]
+        dry_run: bool,
+
+        /// Interactive mode (default: true or config)
+        #[arg(short, long)]
+        interactive: Option<bool>,
+
+        /// Allow restarting processes of other users (requires privileges)
+        #[arg(long)]
+        all_users: bool,
+
+        /// Also restart listeners covered by [kill] protect in the config
+        #[arg(long)]
+        override_protection: bool,
+    },
+
+    /// Operate on a whole docker compose project
+    Stack {
+        /// Compose project name (as shown above its listeners in `scan`)
+        project: String,
+
+        /// down: remove its containers, restart: restart them, ports: list published ports
+        #[arg(value_enum)]
+        action: StackAction,
+
+        /// Dry run (don't actually stop anything)
+        #[arg(long)]
+        dry_run: bool,
+
+        /// Interactive mode (default: true or config)
+        #[arg(short, long)]
+        interactive: Option<bool>,
+
+        /// Also stop containers covered by [kill] protect in the config
+        #[arg(long)]
+        override_protection: bool,
+    },
+
+    /// Audit listeners for network exposure (exits non-zero on policy violations)
+    Audit {
+        /// Start of port range (default: 1)
+        #[arg(long, default_value_t = 1)]
+        from: u16,
+
+        /// End of port range (default: 65535)
+        #[arg(long, default_value_t = 65535)]
+        to: u16,
+
+        /// Only report listeners that violate the policy
+        #[arg(long)]
+        violations_only: bool,
+
+        /// Output as JSON
+        #[arg(long)]
+        json: bool,
     },
 
     /// Start interactive TUI mode
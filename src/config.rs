@@ -1,3 +1,6 @@
+use crate::core::classify::RuleConfig;
+use crate::core::process::Exposure;
+use crate::core::BackendStrategy;
 use anyhow::{Context, Result};
 use serde::Deserialize;
 use std::fs;
@@ -12,6 +15,12 @@ pub struct Config {
     #[serde(default)]
     #[allow(dead_code)]
     pub ui: UiConfig,
+    #[serde(default)]
+    pub backend: BackendConfig,
+    #[serde(default)]
+    pub audit: AuditConfig,
+    #[serde(default)]
+    pub classify: ClassifyConfig,
 }
 
 #[derive(Debug, Deserialize, Clone)]
@@ -27,10 +36,82 @@ impl Default for ScanConfig {
     }
 }
 
-#[derive(Debug, Deserialize, Default, Clone)]
+#[derive(Debug, Deserialize, Clone)]
 pub struct KillConfig {
+    // Any signal name ("HUP", "SIGTERM") or number; checked when the config is loaded.
+    // Sent once instead of climbing the ladder.
     pub default_signal: Option<String>,
     pub confirm: Option<bool>,
+    // Signals tried in order until the port is released
+    #[serde(default = "default_ladder")]
+    pub ladder: Vec<EscalationStep>,
+    #[serde(default)]
+    pub protect: ProtectConfig,
+}
+
+impl Default for KillConfig {
+    fn default() -> Self {
+        Self {
+            default_signal: None,
+            confirm: None,
+            ladder: default_ladder(),
+            protect: ProtectConfig::default(),
+        }
+    }
+}
+
+#[derive(Debug, Deserialize, Clone)]
+pub struct EscalationStep {
+    pub signal: String,
+    // How long to wait for the port to be released before the next step
+    pub timeout_ms: u64,
+}
+
+// Listeners `kill` and the TUI refuse to touch without --override-protection
+#[derive(Debug, Deserialize, Default, Clone)]
+#[serde(default)]
+pub struct ProtectConfig {
+    pub ports: Vec<u16>,
+    // Process names or executable file names
+    pub commands: Vec<String>,
+    pub kinds: Vec<String>,
+    // Container names or compose projects
+    pub containers: Vec<String>,
+    pub users: Vec<String>,
+}
+
+// SIGINT (Ctrl+C) lets dev servers clean up; SIGKILL is the last resort
+fn default_ladder() -> Vec<EscalationStep> {
+    [("INT", 2000), ("TERM", 3000), ("KILL", 2000)]
+        .into_iter()
+        .map(|(signal, timeout_ms)| EscalationStep {
+            signal: signal.to_string(),
+            timeout_ms,
+        })
+        .collect()
+}
+
+#[derive(Debug, Deserialize, Default, Clone)]
+pub struct BackendConfig {
+    #[serde(default)]
+    pub strategy: BackendStrategy,
+}
+
+#[derive(Debug, Deserialize, Default, Clone)]
+pub struct AuditConfig {
+    // Kinds that must not be exposed beyond max_exposure (default: dev, docker)
+    pub kinds: Option<Vec<String>>,
+    // loopback | lan | all (default: loopback)
+    pub max_exposure: Option<Exposure>,
+    #[serde(default)]
+    pub allow_ports: Vec<u16>,
+}
+
+#[derive(Debug, Deserialize, Default, Clone)]
+pub struct ClassifyConfig {
+    // Tried in order before the built-in rules; the first match sets the kind
+    #[serde(default)]
+    pub rules: Vec<RuleConfig>,
 }
 
 #[derive(Debug, Deserialize, Default, Clone)]
@@ -39,12 +120,28 @@ pub struct UiConfig {
     pub color: Option<bool>,
 }
 
+impl Config {
+    pub fn validate(&self) -> Result<()> {
+        if let Some(signal) = &self.kill.default_signal {
+            crate::ops::parse_signal(signal).context("kill.default_signal")?;
+        }
+        if self.kill.ladder.is_empty() {
+            anyhow::bail!("kill.ladder must have at least one step");
+        }
+        for step in &self.kill.ladder {
+            crate::ops::parse_signal(&step.signal).context("kill.ladder")?;
+        }
+        Ok(())
+    }
+}
+
 pub fn load_config(cli_path: Option<&PathBuf>) -> Result<Config> {
     // 1. CLI
     if let Some(path) = cli_path {
-        if path.exists() {
-            return load_from_file(path);
+        if !path.exists() {
+            anyhow::bail!("Config file not found: {:?}", path);
         }
+        return load_from_file(path);
     }
 
     // 2. Local (crossport.toml)
@@ -70,5 +167,8 @@ fn load_from_file(path: &std::path::Path) -> Result<Config> {
         .with_context(|| format!("Failed to read config file: {:?}", path))?;
     let config: Config = toml::from_str(&content)
         .with_context(|| format!("Failed to parse config file: {:?}", path))?;
+    config
+        .validate()
+        .with_context(|| format!("Invalid config file: {:?}", path))?;
     Ok(config)
 }
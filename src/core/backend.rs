@@ -0,0 +1,356 @@
+use crate::core::process::Protocol;
+use crate::core::procfs;
+use anyhow::{Context, Result};
+use serde::Deserialize;
+use std::net::{IpAddr, Ipv4Addr, Ipv6Addr};
+use std::process::Command;
+
+const ANY_V4: IpAddr = IpAddr::V4(Ipv4Addr::UNSPECIFIED);
+const ANY_V6: IpAddr = IpAddr::V6(Ipv6Addr::UNSPECIFIED);
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Listener {
+    pub pid: u32,
+    pub port: u16,
+    pub protocol: Protocol,
+    pub addr: IpAddr,
+}
+
+// A source of listening sockets (TCP LISTEN and bound UDP).
+pub trait PortBackend {
+    fn name(&self) -> &'static str;
+    fn listening_ports(&self) -> Result<Vec<Listener>>;
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Deserialize, clap::ValueEnum)]
+#[serde(rename_all = "lowercase")]
+pub enum BackendStrategy {
+    #[default]
+    Auto,
+    Lsof,
+    Netstat,
+    Ss,
+    Procfs,
+}
+
+impl BackendStrategy {
+    pub fn backend(&self) -> Box<dyn PortBackend> {
+        match self {
+            BackendStrategy::Auto => Box::new(AutoBackend),
+            BackendStrategy::Lsof => Box::new(LsofBackend),
+            BackendStrategy::Netstat => Box::new(NetstatBackend),
+            BackendStrategy::Ss => Box::new(SsBackend),
+            BackendStrategy::Procfs => Box::new(ProcfsBackend),
+        }
+    }
+}
+
+// Tries the preferred backends for the current platform in order, returning the first that works.
+pub struct AutoBackend;
+
+impl AutoBackend {
+    fn candidates() -> Vec<Box<dyn PortBackend>> {
+        if cfg!(target_os = "windows") {
+            vec![Box::new(NetstatBackend)]
+        } else if cfg!(target_os = "linux") {
+            vec![
+                Box::new(ProcfsBackend),
+                Box::new(SsBackend),
+                Box::new(LsofBackend),
+            ]
+        } else {
+            vec![Box::new(LsofBackend), Box::new(NetstatBackend)]
+        }
+    }
+}
+
+impl PortBackend for AutoBackend {
+    fn name(&self) -> &'static str {
+        "auto"
+    }
+
+    fn listening_ports(&self) -> Result<Vec<Listener>> {
+        let mut last_err = None;
+        for backend in Self::candidates() {
+            match backend.listening_ports() {
+                Ok(ports) => return Ok(ports),
+                Err(e) => last_err = Some(e),
+            }
+        }
+        Err(last_err.unwrap_or_else(|| anyhow::anyhow!("No port backend available")))
+    }
+}
+
+pub struct ProcfsBackend;
+
+impl PortBackend for ProcfsBackend {
+    fn name(&self) -> &'static str {
+        "procfs"
+    }
+
+    fn listening_ports(&self) -> Result<Vec<Listener>> {
+        procfs::scan_ports()
+    }
+}
+
+pub struct LsofBackend;
+
+impl PortBackend for LsofBackend {
+    fn name(&self) -> &'static str {
+        "lsof"
+    }
+
+    fn listening_ports(&self) -> Result<Vec<Listener>> {
+        // -sTCP:LISTEN suppresses UDP entirely, so TCP and UDP need separate invocations
+        let mut results = Vec::new();
+        for selector in [&["-iTCP", "-sTCP:LISTEN"][..], &["-iUDP"][..]] {
+            let output = Command::new("lsof")
+                .args(selector)
+                .arg("-P")
+                .arg("-n")
+                .arg("-F")
+                .arg("pPtn") // Request PID ('p'), Protocol ('P'), Type ('t') and Name ('n') for network files
+                .output()
+                .context("Failed to execute lsof for scanning")?;
+
+            results.extend(parse_lsof(&String::from_utf8_lossy(&output.stdout)));
+        }
+        Ok(results)
+    }
+}
+
+pub fn parse_lsof(stdout: &str) -> Vec<Listener> {
+    let mut results = Vec::new();
+    let mut current_pid = None;
+    let mut current_protocol = Protocol::Tcp;
+    let mut current_wildcard = ANY_V4;
+
+    for line in stdout.lines() {
+        if let Some(stripped) = line.strip_prefix('p') {
+            current_pid = stripped.parse::<u32>().ok();
+        } else if let Some(stripped) = line.strip_prefix('t') {
+            // File type tells the family of "*:port" names
+            current_wildcard = if stripped == "IPv6" { ANY_V6 } else { ANY_V4 };
+        } else if let Some(stripped) = line.strip_prefix('P') {
+            current_protocol = parse_protocol(stripped).unwrap_or(Protocol::Tcp);
+        } else if let Some(stripped) = line.strip_prefix('n') {
+            // Connected sockets (n127.0.0.1:5000->127.0.0.1:53) are clients, not listeners
+            if stripped.contains("->") {
+                continue;
+            }
+            if let Some(pid) = current_pid {
+                // Example: n*:12345, n127.0.0.1:5432, n[::1]:3000
+                if let Some((addr, port)) = parse_socket_addr(stripped, current_wildcard) {
+                    results.push(Listener {
+                        pid,
+                        port,
+                        protocol: current_protocol,
+                        addr,
+                    });
+                }
+            }
+        }
+    }
+    results
+}
+
+// Splits "host:port" as printed by lsof/ss/netstat ("*:22", "[::1]:80", "127.0.0.53%lo:53",
+// ":::22", BSD-style "127.0.0.1.5432") and resolves the host, using `wildcard` for "*".
+pub fn parse_socket_addr(s: &str, wildcard: IpAddr) -> Option<(IpAddr, u16)> {
+    let colon = s
+        .rsplit_once(':')
+        .and_then(|(host, port)| Some((host, port.parse::<u16>().ok()?)));
+    let (host, port) = match colon {
+        Some(split) => split,
+        None => {
+            let (host, port) = s.rsplit_once('.')?;
+            (host, port.parse::<u16>().ok()?)
+        }
+    };
+
+    let host = host.trim_start_matches('[').trim_end_matches(']');
+    // Drop zone / interface suffixes (fe80::1%eth0, 127.0.0.53%lo)
+    let host = host.split('%').next().unwrap_or(host);
+    let addr = if host == "*" {
+        wildcard
+    } else {
+        // IPv4-mapped addresses (::ffff:127.0.0.1) are reported as plain IPv4
+        host.parse::<IpAddr>().ok()?.to_canonical()
+    };
+    Some((addr, port))
+}
+
+fn parse_protocol(s: &str) -> Option<Protocol> {
+    let lower = s.to_lowercase();
+    if lower.starts_with("tcp") {
+        Some(Protocol::Tcp)
+    } else if lower.starts_with("udp") {
+        Some(Protocol::Udp)
+    } else {
+        None
+    }
+}
+
+pub struct SsBackend;
+
+impl PortBackend for SsBackend {
+    fn name(&self) -> &'static str {
+        "ss"
+    }
+
+    fn listening_ports(&self) -> Result<Vec<Listener>> {
+        let output = Command::new("ss")
+            .arg("-l")
+            .arg("-t")
+            .arg("-u")
+            .arg("-n")
+            .arg("-p")
+            .output()
+            .context("Failed to execute ss")?;
+
+        if !output.status.success() {
+            anyhow::bail!("ss exited with {}", output.status);
+        }
+        Ok(parse_ss(&String::from_utf8_lossy(&output.stdout)))
+    }
+}
+
+pub fn parse_ss(stdout: &str) -> Vec<Listener> {
+    let mut results = Vec::new();
+
+    for line in stdout.lines() {
+        // Expected format: [Netid] State Recv-Q Send-Q Local Peer Process
+        // Example: tcp LISTEN 0 128 0.0.0.0:22 0.0.0.0:* users:(("sshd",pid=812,fd=3))
+        let mut parts: Vec<&str> = line.split_whitespace().collect();
+        let protocol = match parts.first().and_then(|p| parse_protocol(p)) {
+            Some(protocol) => {
+                parts.remove(0);
+                protocol
+            }
+            None => Protocol::Tcp,
+        };
+        if parts.len() < 6 || !(parts[0] == "LISTEN" || parts[0] == "UNCONN") {
+            continue;
+        }
+
+        // "*" is a dual-stack IPv6 socket
+        let (addr, port) = match parse_socket_addr(parts[3], ANY_V6) {
+            Some(parsed) => parsed,
+            None => continue,
+        };
+
+        // One socket can be shared by several processes (e.g. pre-forked workers)
+        for segment in parts[5..].join(" ").split("pid=").skip(1) {
+            let pid_str: String = segment.chars().take_while(|c| c.is_ascii_digit()).collect();
+            if let Ok(pid) = pid_str.parse::<u32>() {
+                let listener = Listener {
+                    pid,
+                    port,
+                    protocol,
+                    addr,
+                };
+                if !results.contains(&listener) {
+                    results.push(listener);
+                }
+            }
+        }
+    }
+    results
+}
+
+pub struct NetstatBackend;
+
+impl PortBackend for NetstatBackend {
+    fn name(&self) -> &'static str {
+        "netstat"
+    }
+
+    fn listening_ports(&self) -> Result<Vec<Listener>> {
+        let mut cmd = Command::new("netstat");
+        if cfg!(target_os = "windows") {
+            cmd.arg("-ano");
+        } else if cfg!(target_os = "linux") {
+            cmd.arg("-tulnp");
+        } else {
+            cmd.arg("-anv");
+        }
+
+        let output = cmd.output().context("Failed to execute netstat")?;
+        Ok(parse_netstat(&String::from_utf8_lossy(&output.stdout)))
+    }
+}
+
+pub fn parse_netstat(stdout: &str) -> Vec<Listener> {
+    let mut results = Vec::new();
+
+    for line in stdout.lines() {
+        // Windows: TCP    0.0.0.0:8088     0.0.0.0:0    LISTENING    31715
+        //          UDP    0.0.0.0:5353     *:*                       4120
+        // Linux:   tcp    0  0 0.0.0.0:22   0.0.0.0:*    LISTEN       812/sshd
+        //          udp    0  0 0.0.0.0:68   0.0.0.0:*                 640/dhclient
+        // macOS:   tcp4   0  0 *.3000       *.*          LISTEN       131072 131072 4242 0 ...
+        let parts: Vec<&str> = line.split_whitespace().collect();
+        let protocol = match parts.first().and_then(|p| parse_protocol(p)) {
+            Some(protocol) => protocol,
+            None => continue,
+        };
+
+        // Index of the local address column and the start of the trailing (pid) columns
+        let (local_idx, tail_idx) = match protocol {
+            Protocol::Tcp => match parts
+                .iter()
+                .position(|p| *p == "LISTEN" || *p == "LISTENING")
+            {
+                Some(idx) if idx >= 2 => (idx - 2, idx + 1),
+                _ => continue,
+            },
+            Protocol::Udp => {
+                // UDP has no state column; Windows omits the queue columns
+                let local_idx = if parts[0] == "UDP" { 1 } else { 3 };
+                // Only unconnected sockets (foreign address *:*, 0.0.0.0:*, *.*) are listeners
+                match parts.get(local_idx + 1) {
+                    Some(foreign) if foreign.ends_with('*') => (local_idx, local_idx + 2),
+                    _ => continue,
+                }
+            }
+        };
+
+        // tcp6 / udp6 / tcp46 wildcards are IPv6 sockets
+        let wildcard = if parts[0].ends_with('6') {
+            ANY_V6
+        } else {
+            ANY_V4
+        };
+        let (addr, port) = match parts
+            .get(local_idx)
+            .and_then(|local| parse_socket_addr(local, wildcard))
+        {
+            Some(parsed) => parsed,
+            None => continue,
+        };
+
+        let tail = parts.get(tail_idx..).unwrap_or_default();
+        let pid = match tail.first() {
+            // Windows prints a bare PID, Linux prints PID/Program
+            Some(first) if tail.len() == 1 || first.contains('/') => {
+                first.split('/').next().and_then(|p| p.parse::<u32>().ok())
+            }
+            // BSD layout: rhiwat shiwat pid ... (newer releases print process:pid instead)
+            Some(_) => tail
+                .iter()
+                .find_map(|p| p.rsplit_once(':').and_then(|(_, pid)| pid.parse().ok()))
+                .or_else(|| tail.get(2).and_then(|p| p.parse().ok())),
+            None => None,
+        };
+
+        if let Some(pid) = pid {
+            results.push(Listener {
+                pid,
+                port,
+                protocol,
+                addr,
+            });
+        }
+    }
+    results
+}
@@ -0,0 +1,257 @@
+use crate::core::process::ProcessKind;
+use anyhow::{Context, Result};
+use regex::Regex;
+use serde::Deserialize;
+use std::path::Path;
+
+// One `[[classify.rules]]` entry. Every criterion that is set must match; the first matching
+// rule decides the kind. User rules run before the built-in defaults.
+#[derive(Debug, Deserialize, Default, Clone)]
+pub struct RuleConfig {
+    pub kind: String,
+    // Regex on the process name (e.g. "^(node|bun)$")
+    pub cmd: Option<String>,
+    // Regex on the full command line (e.g. "next dev")
+    pub args: Option<String>,
+    // Regex on the executable path
+    pub exe: Option<String>,
+    // Glob on the working directory; `*` stays within a path segment, `**` spans segments
+    pub cwd: Option<String>,
+    pub user: Option<String>,
+    // "3000-3999" or a single port
+    pub ports: Option<String>,
+    // true: only containerized listeners, false: only non-containerized ones
+    pub container: Option<bool>,
+}
+
+pub struct ClassifyContext<'a> {
+    pub cmd: &'a str,
+    pub args: &'a [String],
+    pub exe: Option<&'a Path>,
+    pub cwd: &'a Path,
+    pub user: &'a str,
+    pub port: u16,
+    pub has_container: bool,
+}
+
+struct Rule {
+    kind: ProcessKind,
+    cmd: Option<Regex>,
+    args: Option<Regex>,
+    exe: Option<Regex>,
+    cwd: Option<Regex>,
+    user: Option<String>,
+    ports: Option<(u16, u16)>,
+    container: Option<bool>,
+}
+
+impl Rule {
+    fn compile(config: &RuleConfig) -> Result<Self> {
+        let regex = |field: &str, pattern: &Option<String>| -> Result<Option<Regex>> {
+            pattern
+                .as_deref()
+                .map(|p| {
+                    Regex::new(p).with_context(|| {
+                        format!("Invalid {} regex in rule for '{}'", field, config.kind)
+                    })
+                })
+                .transpose()
+        };
+
+        Ok(Self {
+            kind: ProcessKind::from_name(&config.kind),
+            cmd: regex("cmd", &config.cmd)?,
+            args: regex("args", &config.args)?,
+            exe: regex("exe", &config.exe)?,
+            cwd: config.cwd.as_deref().map(glob_to_regex).transpose()?,
+            user: config.user.clone(),
+            ports: config
+                .ports
+                .as_deref()
+                .map(|p| {
+                    parse_port_range(p).with_context(|| {
+                        format!("Invalid port range '{}' in rule for '{}'", p, config.kind)
+                    })
+                })
+                .transpose()?,
+            container: config.container,
+        })
+    }
+
+    fn matches(&self, ctx: &ClassifyContext) -> bool {
+        if let Some(re) = &self.cmd {
+            if !re.is_match(ctx.cmd) {
+                return false;
+            }
+        }
+        if let Some(re) = &self.args {
+            if !re.is_match(&ctx.args.join(" ")) {
+                return false;
+            }
+        }
+        if let Some(re) = &self.exe {
+            match ctx.exe {
+                Some(exe) if re.is_match(&exe.to_string_lossy()) => {}
+                _ => return false,
+            }
+        }
+        if let Some(re) = &self.cwd {
+            if !re.is_match(&ctx.cwd.to_string_lossy()) {
+                return false;
+            }
+        }
+        if let Some(user) = &self.user {
+            if user != ctx.user {
+                return false;
+            }
+        }
+        if let Some((from, to)) = self.ports {
+            if ctx.port < from || ctx.port > to {
+                return false;
+            }
+        }
+        if let Some(container) = self.container {
+            if container != ctx.has_container {
+                return false;
+            }
+        }
+        true
+    }
+}
+
+pub struct Classifier {
+    rules: Vec<Rule>,
+}
+
+impl Classifier {
+    pub fn new(user_rules: &[RuleConfig]) -> Result<Self> {
+        let mut rules = user_rules
+            .iter()
+            .map(Rule::compile)
+            .collect::<Result<Vec<_>>>()?;
+        rules.extend(
+            builtin_rules()
+                .iter()
+                .map(|r| Rule::compile(r).expect("built-in rules are valid")),
+        );
+        Ok(Self { rules })
+    }
+
+    pub fn classify(&self, ctx: &ClassifyContext) -> ProcessKind {
+        self.rules
+            .iter()
+            .find(|r| r.matches(ctx))
+            .map(|r| r.kind.clone())
+            .unwrap_or(ProcessKind::Other)
+    }
+}
+
+// The historical hard-coded heuristics, expressed as rules.
+fn builtin_rules() -> Vec<RuleConfig> {
+    let rule = |kind: &str| RuleConfig {
+        kind: kind.to_string(),
+        ..Default::default()
+    };
+
+    vec![
+        RuleConfig {
+            container: Some(true),
+            ..rule("docker")
+        },
+        // kubectl / oc (also via a full path, k3s, microk8s or microk8s.kubectl) port-forward
+        RuleConfig {
+            args: Some(
+                r"^(\S*[/\\])?(kubectl|oc|k3s|microk8s|microk8s\.kubectl)(\.exe)?\s(.*\s)?port-forward(\s|$)".to_string(),
+            ),
+            ..rule("k8s")
+        },
+        RuleConfig {
+            cmd: Some("^(k9s|telepresence)$".to_string()),
+            ..rule("k8s")
+        },
+        RuleConfig {
+            cmd: Some("docker|containerd".to_string()),
+            ..rule("docker")
+        },
+        // Port forwarders of rootless Podman / Docker / nerdctl
+        RuleConfig {
+            cmd: Some(
+                r"^(rootlessport|rootlessport-child|rootlesskit|pasta|pasta\.avx2|passt|slirp4netns|conmon)$"
+                    .to_string(),
+            ),
+            ..rule("docker")
+        },
+        // ssh -L / -D / -R (possibly bundled as -fNL), autossh, and sshuttle (a python script)
+        RuleConfig {
+            cmd: Some("^(ssh|autossh)$".to_string()),
+            args: Some(r"\s-[1246AaCfGgKkMNnqsTtVvXxYy]*[LDR]".to_string()),
+            ..rule("tunnel")
+        },
+        RuleConfig {
+            args: Some(r"^(\S*/)?(python\S*\s+)?\S*sshuttle(\s|$)".to_string()),
+            ..rule("tunnel")
+        },
+        RuleConfig {
+            cwd: Some("**/_workspace/**".to_string()),
+            ..rule("dev")
+        },
+        RuleConfig {
+            cwd: Some("**/_projects/**".to_string()),
+            ..rule("dev")
+        },
+        RuleConfig {
+            cwd: Some("/usr/sbin**".to_string()),
+            ..rule("system")
+        },
+        RuleConfig {
+            cwd: Some("/System**".to_string()),
+            ..rule("system")
+        },
+        RuleConfig {
+            cwd: Some("**/opt/homebrew**".to_string()),
+            ..rule("brew")
+        },
+        RuleConfig {
+            cwd: Some("**/usr/local/Cellar**".to_string()),
+            ..rule("brew")
+        },
+    ]
+}
+
+pub fn glob_to_regex(glob: &str) -> Result<Regex> {
+    let expanded = match (glob.strip_prefix('~'), dirs::home_dir()) {
+        (Some(rest), Some(home)) => format!("{}{}", home.display(), rest),
+        _ => glob.to_string(),
+    };
+
+    let mut pattern = String::from("^");
+    let mut chars = expanded.chars().peekable();
+    while let Some(c) = chars.next() {
+        match c {
+            '*' if chars.peek() == Some(&'*') => {
+                chars.next();
+                pattern.push_str(".*");
+            }
+            '*' => pattern.push_str("[^/]*"),
+            '?' => pattern.push_str("[^/]"),
+            _ => pattern.push_str(&regex::escape(&c.to_string())),
+        }
+    }
+    pattern.push('$');
+
+    Regex::new(&pattern).with_context(|| format!("Invalid glob: {}", glob))
+}
+
+pub fn parse_port_range(s: &str) -> Result<(u16, u16)> {
+    let (from, to) = match s.split_once('-') {
+        Some((from, to)) => (from.trim().parse::<u16>()?, to.trim().parse::<u16>()?),
+        None => {
+            let port = s.trim().parse::<u16>()?;
+            (port, port)
+        }
+    };
+    if from > to {
+        anyhow::bail!("Range start {} is after end {}", from, to);
+    }
+    Ok((from, to))
+}
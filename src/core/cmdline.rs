@@ -0,0 +1,129 @@
+use std::path::Path;
+
+// Turns a raw argv into something that tells processes apart at a glance:
+// `node /app/node_modules/.bin/next dev` -> `next dev`, `python -m uvicorn app:api` -> `uvicorn app:api`.
+
+const MAX_TOKENS: usize = 4;
+
+const INTERPRETERS: &[&str] = &[
+    "node", "nodejs", "bun", "deno", "python", "python2", "python3", "ruby", "perl", "php", "java",
+];
+
+// Interpreter flags that consume the following argument
+const FLAGS_WITH_VALUE: &[&str] = &[
+    "-r",
+    "--require",
+    "--import",
+    "--loader",
+    "--experimental-loader",
+    "-W",
+    "-X",
+    "-cp",
+    "-classpath",
+];
+
+const INLINE_CODE_FLAGS: &[&str] = &["-c", "-e", "--eval", "-p", "--print"];
+
+// Script names that say nothing on their own; the owning package name is used instead
+const GENERIC_SCRIPTS: &[&str] = &["cli", "index", "main", "bin", "run", "start", "server"];
+
+pub fn display_command(name: &str, args: &[String]) -> String {
+    let program = args.first().map(|a| base_name(a)).unwrap_or(name);
+    let rest = args.get(1..).unwrap_or_default();
+
+    if !is_interpreter(program) {
+        return join_tokens(program, rest);
+    }
+
+    let mut i = 0;
+    while i < rest.len() {
+        let arg = rest[i].as_str();
+        if arg == "-m" || arg == "-jar" {
+            // python -m module / java -jar app.jar name the entry point explicitly
+            return match rest.get(i + 1) {
+                Some(entry) => join_tokens(base_name(entry), &rest[i + 2..]),
+                None => program.to_string(),
+            };
+        }
+        if INLINE_CODE_FLAGS.contains(&arg) {
+            // python -c '...' / node -e '...': the code itself is noise
+            return format!("{} {}", program, arg);
+        }
+        if FLAGS_WITH_VALUE.contains(&arg) {
+            i += 2;
+            continue;
+        }
+        if arg.starts_with('-') {
+            i += 1;
+            continue;
+        }
+        return join_tokens(&script_name(arg), &rest[i + 1..]);
+    }
+
+    program.to_string()
+}
+
+fn is_interpreter(program: &str) -> bool {
+    // python3.12, node18 etc. count as their base interpreter
+    let stem = program.trim_end_matches(|c: char| c.is_ascii_digit() || c == '.');
+    INTERPRETERS.contains(&program) || INTERPRETERS.contains(&stem)
+}
+
+fn script_name(path: &str) -> String {
+    let p = Path::new(path);
+    let file = base_name(path);
+    if !path.contains("node_modules") {
+        return file.to_string();
+    }
+
+    let stem = p
+        .file_stem()
+        .map(|s| s.to_string_lossy().to_string())
+        .unwrap_or_else(|| file.to_string());
+
+    if GENERIC_SCRIPTS.contains(&stem.as_str()) {
+        // node_modules/<pkg>/bin/cli.js -> <pkg> (scoped: node_modules/@scope/pkg)
+        let components: Vec<_> = p
+            .components()
+            .map(|c| c.as_os_str().to_string_lossy().to_string())
+            .collect();
+        if let Some(pos) = components.iter().rposition(|c| c == "node_modules") {
+            match components.get(pos + 1) {
+                Some(scope) if scope.starts_with('@') => {
+                    if let Some(pkg) = components.get(pos + 2) {
+                        return format!("{}/{}", scope, pkg);
+                    }
+                }
+                Some(pkg) if pkg != ".bin" => return pkg.clone(),
+                _ => {}
+            }
+        }
+    }
+    stem
+}
+
+fn join_tokens(head: &str, rest: &[String]) -> String {
+    let mut tokens = vec![head.to_string()];
+    tokens.extend(
+        rest.iter()
+            .take(MAX_TOKENS - 1)
+            .map(|a| base_name(a).to_string()),
+    );
+    // Collapse embedded newlines/tabs so the result always fits on one line
+    tokens
+        .join(" ")
+        .split_whitespace()
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+fn base_name(arg: &str) -> &str {
+    // URLs (--url=http://...) are not paths
+    if arg.contains("://") {
+        return arg;
+    }
+    match arg.rsplit(['/', '\\']).next() {
+        Some(name) if !name.is_empty() => name,
+        _ => arg,
+    }
+}
@@ -0,0 +1,530 @@
+use crate::core::docker::{DockerClient, Endpoint};
+use crate::core::process::Protocol;
+use anyhow::{Context, Result};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::net::IpAddr;
+use std::path::PathBuf;
+use std::process::Command;
+use std::sync::Mutex;
+
+// Container runtimes that can publish host ports. Docker and Podman are asked through their
+// API sockets; their CLIs are the fallback when no socket answers (Windows named pipes,
+// DOCKER_HOST=ssh://..., TLS, podman without its service), and nerdctl only has a CLI.
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+pub enum ContainerRuntime {
+    Docker,
+    Podman,
+    Nerdctl,
+}
+
+impl ContainerRuntime {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            ContainerRuntime::Docker => "docker",
+            ContainerRuntime::Podman => "podman",
+            ContainerRuntime::Nerdctl => "nerdctl",
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, clap::ValueEnum)]
+pub enum ContainerAction {
+    #[default]
+    Stop,
+    Restart,
+    Kill,
+    // Force-remove (stops it first)
+    Rm,
+}
+
+impl ContainerAction {
+    pub fn verb(&self) -> &'static str {
+        match self {
+            ContainerAction::Stop => "Stop",
+            ContainerAction::Restart => "Restart",
+            ContainerAction::Kill => "Kill",
+            ContainerAction::Rm => "Remove",
+        }
+    }
+
+    pub fn past_tense(&self) -> &'static str {
+        match self {
+            ContainerAction::Stop => "Stopped",
+            ContainerAction::Restart => "Restarted",
+            ContainerAction::Kill => "Killed",
+            ContainerAction::Rm => "Removed",
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct PortMapping {
+    pub host_ip: Option<IpAddr>,
+    pub host_port: u16,
+    pub container_port: u16,
+    pub protocol: Protocol,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct ContainerInfo {
+    pub id: String,
+    pub runtime: ContainerRuntime,
+    // API socket the container was listed from; None when it came from a CLI
+    #[serde(skip)]
+    pub endpoint: Option<Endpoint>,
+    pub name: String,
+    pub image: String,
+    // created | running | paused | restarting | exited ...
+    pub state: String,
+    // Human-readable, e.g. "Up 2 hours (healthy)"
+    pub status: String,
+    // healthy | unhealthy | starting; None without a HEALTHCHECK
+    pub health: Option<String>,
+    pub compose_project: Option<String>,
+    pub compose_service: Option<String>,
+    pub ports: Vec<PortMapping>,
+}
+
+impl ContainerInfo {
+    pub fn short_id(&self) -> &str {
+        self.id.get(..12).unwrap_or(&self.id)
+    }
+
+    // Goes through the runtime rather than signalling the proxy process that holds the port,
+    // which would only break the container's networking
+    pub fn apply(&self, action: ContainerAction) -> Result<()> {
+        if let Some(endpoint) = &self.endpoint {
+            return DockerClient::new(endpoint.clone()).container_action(&self.id, action);
+        }
+
+        let args: &[&str] = match action {
+            ContainerAction::Stop => &["stop"],
+            ContainerAction::Restart => &["restart"],
+            ContainerAction::Kill => &["kill"],
+            ContainerAction::Rm => &["rm", "-f"],
+        };
+        let program = self.runtime.as_str();
+        let output = Command::new(program)
+            .args(args)
+            .arg(&self.id)
+            .output()
+            .with_context(|| format!("Failed to execute {}", program))?;
+        if !output.status.success() {
+            anyhow::bail!(
+                "{} {} {} failed: {}",
+                program,
+                args.join(" "),
+                self.name,
+                String::from_utf8_lossy(&output.stderr).trim()
+            );
+        }
+        Ok(())
+    }
+}
+
+// Running containers of every runtime that answers
+pub fn list_containers() -> Vec<ContainerInfo> {
+    let mut containers = Vec::new();
+
+    let docker = first_answering(Endpoint::discover()).or_else(|| {
+        run_cli("docker", &["ps", "--format", "{{json .}}"]).map(|out| parse_docker_ps(&out))
+    });
+    if let Some(docker) = docker {
+        containers.extend(docker);
+    }
+
+    let podman = first_answering(podman_endpoints()).or_else(|| {
+        run_cli("podman", &["ps", "--format", "json"]).and_then(|out| parse_podman_ps(&out).ok())
+    });
+    if let Some(podman) = podman {
+        containers.extend(podman.into_iter().map(|mut c| {
+            c.runtime = ContainerRuntime::Podman;
+            c
+        }));
+    }
+
+    if let Some(out) = run_cli("nerdctl", &["ps", "--format", "{{json .}}"]) {
+        containers.extend(parse_nerdctl_ps(&out));
+    }
+
+    containers
+}
+
+// Host port -> container. Rootless runtimes publish ports through a helper process
+// (rootlessport, pasta, slirp4netns, rootlesskit), so matching by host port rather than by PID
+// is what ties those listeners to their container.
+pub fn container_map() -> HashMap<(u16, Protocol), ContainerInfo> {
+    map_by_host_port(list_containers())
+}
+
+pub fn map_by_host_port(containers: Vec<ContainerInfo>) -> HashMap<(u16, Protocol), ContainerInfo> {
+    let mut map = HashMap::new();
+    for container in containers {
+        for mapping in &container.ports {
+            map.insert((mapping.host_port, mapping.protocol), container.clone());
+        }
+    }
+    map
+}
+
+fn first_answering(endpoints: Vec<Endpoint>) -> Option<Vec<ContainerInfo>> {
+    endpoints
+        .into_iter()
+        .find_map(|endpoint| DockerClient::new(endpoint).containers().ok())
+}
+
+// $CONTAINER_HOST, then the rootless user socket, the rootful one and podman machine (macOS)
+fn podman_endpoints() -> Vec<Endpoint> {
+    if let Some(endpoint) = std::env::var("CONTAINER_HOST")
+        .ok()
+        .and_then(|host| Endpoint::parse(&host))
+    {
+        return vec![endpoint];
+    }
+
+    let mut candidates = Vec::new();
+    if let Some(runtime_dir) = runtime_dir() {
+        candidates.push(Endpoint::Unix(runtime_dir.join("podman/podman.sock")));
+    }
+    candidates.push(Endpoint::Unix(PathBuf::from("/run/podman/podman.sock")));
+    if let Some(home) = dirs::home_dir() {
+        let machine = home.join(".local/share/containers/podman/machine");
+        candidates.push(Endpoint::Unix(machine.join("podman.sock")));
+        candidates.push(Endpoint::Unix(machine.join("qemu/podman.sock")));
+    }
+    candidates
+}
+
+// $XDG_RUNTIME_DIR, or /run/user/<uid> where it is not exported (sudo, cron)
+pub fn runtime_dir() -> Option<PathBuf> {
+    if let Some(dir) = std::env::var_os("XDG_RUNTIME_DIR").filter(|d| !d.is_empty()) {
+        return Some(PathBuf::from(dir));
+    }
+    #[cfg(unix)]
+    {
+        let dir = PathBuf::from(format!("/run/user/{}", nix::unistd::getuid().as_raw()));
+        if dir.is_dir() {
+            return Some(dir);
+        }
+    }
+    None
+}
+
+// Runtimes whose CLI is not installed. The TUI captures every couple of seconds, so a missing
+// binary is only looked for once per run.
+static MISSING_CLIS: Mutex<Vec<&str>> = Mutex::new(Vec::new());
+
+// Output of a successful run; a missing binary or a failing daemon yields None
+fn run_cli(program: &'static str, args: &[&str]) -> Option<String> {
+    if MISSING_CLIS
+        .lock()
+        .is_ok_and(|missing| missing.contains(&program))
+    {
+        return None;
+    }
+    let output = match Command::new(program).args(args).output() {
+        Ok(output) => output,
+        Err(e) => {
+            if e.kind() == std::io::ErrorKind::NotFound {
+                if let Ok(mut missing) = MISSING_CLIS.lock() {
+                    missing.push(program);
+                }
+            }
+            return None;
+        }
+    };
+    output
+        .status
+        .success()
+        .then(|| String::from_utf8_lossy(&output.stdout).to_string())
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "PascalCase")]
+struct PodmanContainer {
+    id: String,
+    #[serde(default)]
+    names: Vec<String>,
+    #[serde(default)]
+    image: String,
+    #[serde(default)]
+    labels: Option<HashMap<String, String>>,
+    #[serde(default)]
+    state: String,
+    #[serde(default)]
+    status: String,
+    #[serde(default)]
+    ports: Option<Vec<PodmanPort>>,
+}
+
+#[derive(Deserialize)]
+struct PodmanPort {
+    #[serde(default)]
+    host_ip: String,
+    container_port: u16,
+    host_port: u16,
+    // Consecutive ports published by one `-p 8000-8010:8000-8010`
+    #[serde(default)]
+    range: u16,
+    protocol: String,
+}
+
+// `podman ps --format json`
+pub fn parse_podman_ps(json: &str) -> Result<Vec<ContainerInfo>> {
+    let raw: Vec<PodmanContainer> =
+        serde_json::from_str(json).context("Unexpected podman ps output")?;
+    Ok(raw
+        .into_iter()
+        .map(|c| {
+            let labels = c.labels.unwrap_or_default();
+            let ports = c
+                .ports
+                .unwrap_or_default()
+                .iter()
+                .flat_map(|p| {
+                    let protocol = parse_protocol(&p.protocol);
+                    let host_ip = p.host_ip.parse().ok();
+                    (0..p.range.max(1)).filter_map(move |offset| {
+                        Some(PortMapping {
+                            host_ip,
+                            host_port: p.host_port.checked_add(offset)?,
+                            container_port: p.container_port.checked_add(offset)?,
+                            protocol: protocol?,
+                        })
+                    })
+                })
+                .collect();
+            ContainerInfo {
+                name: c
+                    .names
+                    .first()
+                    .cloned()
+                    .unwrap_or_else(|| c.id.chars().take(12).collect()),
+                runtime: ContainerRuntime::Podman,
+                endpoint: None,
+                health: parse_health(&c.status),
+                compose_project: compose_label(&labels, "project"),
+                compose_service: compose_label(&labels, "service"),
+                id: c.id,
+                image: c.image,
+                state: c.state,
+                status: c.status,
+                ports,
+            }
+        })
+        .collect())
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "PascalCase")]
+struct NerdctlContainer {
+    #[serde(rename = "ID")]
+    id: String,
+    #[serde(default)]
+    names: String,
+    #[serde(default)]
+    image: String,
+    // "key=value,key=value"
+    #[serde(default)]
+    labels: String,
+    #[serde(default)]
+    status: String,
+    // "0.0.0.0:8080->80/tcp, :::8080->80/tcp"
+    #[serde(default)]
+    ports: String,
+}
+
+// `docker ps --format '{{json .}}'`, which nerdctl mirrors
+pub fn parse_docker_ps(output: &str) -> Vec<ContainerInfo> {
+    parse_ps_lines(output, ContainerRuntime::Docker)
+}
+
+// `nerdctl ps --format '{{json .}}'`: one JSON object per line
+pub fn parse_nerdctl_ps(output: &str) -> Vec<ContainerInfo> {
+    parse_ps_lines(output, ContainerRuntime::Nerdctl)
+}
+
+fn parse_ps_lines(output: &str, runtime: ContainerRuntime) -> Vec<ContainerInfo> {
+    output
+        .lines()
+        .filter_map(|line| serde_json::from_str::<NerdctlContainer>(line.trim()).ok())
+        .map(|c| {
+            let labels: HashMap<String, String> = c
+                .labels
+                .split(',')
+                .filter_map(|kv| kv.split_once('='))
+                .map(|(k, v)| (k.to_string(), v.to_string()))
+                .collect();
+            let state = if c.status.starts_with("Up") {
+                "running"
+            } else {
+                "exited"
+            };
+            ContainerInfo {
+                name: if c.names.is_empty() {
+                    c.id.chars().take(12).collect()
+                } else {
+                    c.names.clone()
+                },
+                runtime,
+                endpoint: None,
+                image: c.image,
+                state: state.to_string(),
+                health: parse_health(&c.status),
+                compose_project: compose_label(&labels, "project"),
+                compose_service: compose_label(&labels, "service"),
+                ports: parse_port_list(&c.ports),
+                status: c.status,
+                id: c.id,
+            }
+        })
+        .collect()
+}
+
+// Docker-style port summary: "0.0.0.0:8080->80/tcp, [::]:8080->80/tcp, 5432/tcp"
+pub fn parse_port_list(s: &str) -> Vec<PortMapping> {
+    s.split(',')
+        .filter_map(|def| {
+            let (host, container) = def.trim().split_once("->")?;
+            let (container_port, protocol) = container.split_once('/')?;
+            let (host_ip, host_port) = host.rsplit_once(':')?;
+            let host_ip = host_ip.trim_start_matches('[').trim_end_matches(']');
+            Some(PortMapping {
+                host_ip: host_ip.parse().ok(),
+                host_port: host_port.parse().ok()?,
+                container_port: container_port.parse().ok()?,
+                protocol: parse_protocol(protocol)?,
+            })
+        })
+        .collect()
+}
+
+pub fn parse_protocol(s: &str) -> Option<Protocol> {
+    match s {
+        "tcp" => Some(Protocol::Tcp),
+        "udp" => Some(Protocol::Udp),
+        // sctp is not tracked by any backend
+        _ => None,
+    }
+}
+
+// docker compose and nerdctl compose use com.docker.compose.*, podman-compose adds io.podman.compose.*
+pub fn compose_label(labels: &HashMap<String, String>, key: &str) -> Option<String> {
+    labels
+        .get(&format!("com.docker.compose.{}", key))
+        .or_else(|| labels.get(&format!("io.podman.compose.{}", key)))
+        .cloned()
+}
+
+// "Up 3 minutes (healthy)" / "Up 5 seconds (health: starting)"
+pub fn parse_health(status: &str) -> Option<String> {
+    let inner = status.rsplit_once('(')?.1.strip_suffix(')')?;
+    let health = inner.strip_prefix("health: ").unwrap_or(inner);
+    matches!(health, "healthy" | "unhealthy" | "starting").then(|| health.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_podman_and_nerdctl_parsing() {
+        let podman = parse_podman_ps(
+            r#"[{"Id": "9c1e2f3a4b5c6d7e", "Names": ["api"], "Image": "localhost/api:dev",
+                 "State": "running", "Status": "Up 3 minutes (health: starting)",
+                 "Labels": {"io.podman.compose.project": "shop", "io.podman.compose.service": "api"},
+                 "Ports": [{"host_ip": "", "container_port": 8000, "host_port": 18000, "range": 3, "protocol": "tcp"}]},
+                {"Id": "0000", "Names": ["idle"], "Image": "busybox", "State": "running",
+                 "Status": "Up", "Labels": null, "Ports": null}]"#,
+        )
+        .unwrap();
+        assert_eq!(podman.len(), 2);
+        let api = &podman[0];
+        assert_eq!(api.runtime, ContainerRuntime::Podman);
+        assert_eq!(api.health.as_deref(), Some("starting"));
+        assert_eq!(api.compose_project.as_deref(), Some("shop"));
+        let host_ports: Vec<u16> = api.ports.iter().map(|p| p.host_port).collect();
+        assert_eq!(host_ports, vec![18000, 18001, 18002]);
+        assert_eq!(api.ports[2].container_port, 8002);
+
+        let nerdctl = parse_nerdctl_ps(
+            "{\"ID\":\"5d6e7f80\",\"Names\":\"cache\",\"Image\":\"redis:7\",\"Status\":\"Up\",\"Labels\":\"com.docker.compose.project=shop,com.docker.compose.service=cache\",\"Ports\":\"127.0.0.1:16379->6379/tcp, [::1]:16379->6379/tcp, 53/udp\"}\nnot json\n",
+        );
+        assert_eq!(nerdctl.len(), 1);
+        let cache = &nerdctl[0];
+        assert_eq!(cache.runtime, ContainerRuntime::Nerdctl);
+        assert_eq!(cache.state, "running");
+        assert_eq!(cache.compose_service.as_deref(), Some("cache"));
+        assert_eq!(cache.ports.len(), 2);
+        assert_eq!(cache.ports[1].host_ip, Some("::1".parse().unwrap()));
+
+        let map = map_by_host_port(podman.into_iter().chain(nerdctl).collect());
+        assert_eq!(map[&(18001, Protocol::Tcp)].name, "api");
+        assert_eq!(map[&(16379, Protocol::Tcp)].name, "cache");
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_container_actions_via_api() {
+        use std::io::{Read, Write};
+        use std::os::unix::net::UnixListener;
+
+        let socket =
+            std::env::temp_dir().join(format!("crossport-action-{}.sock", std::process::id()));
+        let _ = std::fs::remove_file(&socket);
+        let listener = UnixListener::bind(&socket).unwrap();
+        let server = std::thread::spawn(move || {
+            // stop -> 204, restart -> 304 (already in that state), rm -> 404
+            let responses = [
+                "HTTP/1.1 204 No Content\r\n\r\n",
+                "HTTP/1.1 304 Not Modified\r\n\r\n",
+                "HTTP/1.1 404 Not Found\r\nContent-Type: application/json\r\n\r\n{\"message\":\"No such container: db\"}",
+            ];
+            let mut requests = Vec::new();
+            for response in responses {
+                let (mut conn, _) = listener.accept().unwrap();
+                let mut buf = [0u8; 1024];
+                let n = conn.read(&mut buf).unwrap();
+                requests.push(
+                    String::from_utf8_lossy(&buf[..n])
+                        .lines()
+                        .next()
+                        .unwrap()
+                        .to_string(),
+                );
+                conn.write_all(response.as_bytes()).unwrap();
+            }
+            requests
+        });
+
+        let container = ContainerInfo {
+            id: "db".to_string(),
+            runtime: ContainerRuntime::Docker,
+            endpoint: Some(Endpoint::Unix(socket.clone())),
+            name: "shop-db-1".to_string(),
+            image: "postgres:16".to_string(),
+            state: "running".to_string(),
+            status: "Up".to_string(),
+            health: None,
+            compose_project: None,
+            compose_service: None,
+            ports: Vec::new(),
+        };
+        container.apply(ContainerAction::Stop).unwrap();
+        container.apply(ContainerAction::Restart).unwrap();
+        let err = container.apply(ContainerAction::Rm).unwrap_err();
+        assert!(format!("{:#}", err).contains("No such container"));
+
+        assert_eq!(
+            server.join().unwrap(),
+            vec![
+                "POST /containers/db/stop HTTP/1.0",
+                "POST /containers/db/restart HTTP/1.0",
+                "DELETE /containers/db?force=true HTTP/1.0",
+            ]
+        );
+        std::fs::remove_file(&socket).unwrap();
+    }
+}
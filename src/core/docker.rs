@@ -0,0 +1,349 @@
+use crate::core::containers::{
+    compose_label, parse_health, parse_protocol, runtime_dir, ContainerAction, ContainerInfo,
+    ContainerRuntime, PortMapping,
+};
+use anyhow::{Context, Result};
+use serde::Deserialize;
+use std::collections::HashMap;
+use std::io::{Read, Write};
+use std::net::TcpStream;
+use std::path::PathBuf;
+use std::time::Duration;
+
+// A minimal Docker Engine API client: plain HTTP/1.0 over the daemon's unix socket (or a
+// tcp:// DOCKER_HOST), so that no `docker` CLI is needed and the output is structured.
+// Podman's service speaks the same API.
+
+const TIMEOUT: Duration = Duration::from_secs(2);
+// Stopping waits for the container's own shutdown (10s grace period by default)
+const ACTION_TIMEOUT: Duration = Duration::from_secs(30);
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Endpoint {
+    Unix(PathBuf),
+    Tcp(String),
+}
+
+impl Endpoint {
+    // Understands the DOCKER_HOST forms crossport can speak: unix://path and tcp://host:port
+    pub fn parse(s: &str) -> Option<Self> {
+        if let Some(path) = s.strip_prefix("unix://") {
+            Some(Endpoint::Unix(PathBuf::from(path)))
+        } else {
+            s.strip_prefix("tcp://")
+                .map(|addr| Endpoint::Tcp(addr.trim_end_matches('/').to_string()))
+        }
+    }
+
+    // $DOCKER_HOST wins; otherwise the usual socket locations (Linux, rootless, Docker Desktop).
+    // Hosts the client cannot speak to (ssh://, npipe://, TLS) yield nothing, which leaves
+    // them to the `docker` CLI.
+    pub fn discover() -> Vec<Self> {
+        if let Ok(host) = std::env::var("DOCKER_HOST") {
+            // TLS-protected daemons need client certificates we do not handle
+            if std::env::var_os("DOCKER_TLS_VERIFY").is_some_and(|v| !v.is_empty()) {
+                return Vec::new();
+            }
+            return Self::parse(&host).into_iter().collect();
+        }
+
+        let mut candidates = vec![Endpoint::Unix(PathBuf::from("/var/run/docker.sock"))];
+        if let Some(runtime_dir) = runtime_dir() {
+            candidates.push(Endpoint::Unix(runtime_dir.join("docker.sock")));
+        }
+        if let Some(home) = dirs::home_dir() {
+            candidates.push(Endpoint::Unix(home.join(".docker/run/docker.sock")));
+            candidates.push(Endpoint::Unix(home.join(".docker/desktop/docker.sock")));
+        }
+        candidates
+    }
+}
+
+impl std::fmt::Display for Endpoint {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Endpoint::Unix(path) => write!(f, "unix://{}", path.display()),
+            Endpoint::Tcp(addr) => write!(f, "tcp://{}", addr),
+        }
+    }
+}
+
+pub struct DockerClient {
+    endpoint: Endpoint,
+}
+
+impl DockerClient {
+    pub fn new(endpoint: Endpoint) -> Self {
+        Self { endpoint }
+    }
+
+    pub fn containers(&self) -> Result<Vec<ContainerInfo>> {
+        let body = self.request("GET", "/containers/json", TIMEOUT)?;
+        let raw: Vec<ApiContainer> =
+            serde_json::from_slice(&body).context("Unexpected /containers/json response")?;
+        Ok(raw
+            .into_iter()
+            .map(|c| ContainerInfo {
+                endpoint: Some(self.endpoint.clone()),
+                ..ContainerInfo::from(c)
+            })
+            .collect())
+    }
+
+    pub fn container_action(&self, id: &str, action: ContainerAction) -> Result<()> {
+        let (method, path) = match action {
+            ContainerAction::Stop => ("POST", format!("/containers/{}/stop", id)),
+            ContainerAction::Restart => ("POST", format!("/containers/{}/restart", id)),
+            ContainerAction::Kill => ("POST", format!("/containers/{}/kill", id)),
+            ContainerAction::Rm => ("DELETE", format!("/containers/{}?force=true", id)),
+        };
+        self.request(method, &path, ACTION_TIMEOUT)?;
+        Ok(())
+    }
+
+    fn request(&self, method: &str, path: &str, timeout: Duration) -> Result<Vec<u8>> {
+        // HTTP/1.0 makes the daemon close the connection after the response
+        let request = format!(
+            "{} {} HTTP/1.0\r\nHost: docker\r\nContent-Length: 0\r\n\r\n",
+            method, path
+        );
+        let response = match &self.endpoint {
+            #[cfg(unix)]
+            Endpoint::Unix(socket) => {
+                let mut stream = std::os::unix::net::UnixStream::connect(socket)
+                    .with_context(|| format!("Cannot connect to {}", self.endpoint))?;
+                stream.set_read_timeout(Some(timeout))?;
+                stream.set_write_timeout(Some(timeout))?;
+                exchange(&mut stream, &request)?
+            }
+            #[cfg(not(unix))]
+            Endpoint::Unix(_) => anyhow::bail!("Unix sockets are not supported on this platform"),
+            Endpoint::Tcp(addr) => {
+                let mut stream = TcpStream::connect(addr)
+                    .with_context(|| format!("Cannot connect to {}", self.endpoint))?;
+                stream.set_read_timeout(Some(timeout))?;
+                stream.set_write_timeout(Some(timeout))?;
+                exchange(&mut stream, &request)?
+            }
+        };
+        parse_http_response(&response)
+            .with_context(|| format!("{} {} on {} failed", method, path, self.endpoint))
+    }
+}
+
+fn exchange<S: Read + Write>(stream: &mut S, request: &str) -> Result<Vec<u8>> {
+    stream.write_all(request.as_bytes())?;
+    let mut response = Vec::new();
+    stream.read_to_end(&mut response)?;
+    Ok(response)
+}
+
+// Returns the body of a 2xx response, decoding chunked transfer encoding if used. 304 is what
+// the daemon answers when a container is already in the requested state.
+pub fn parse_http_response(response: &[u8]) -> Result<Vec<u8>> {
+    let header_end = response
+        .windows(4)
+        .position(|w| w == b"\r\n\r\n")
+        .context("Truncated HTTP response")?;
+    let head = String::from_utf8_lossy(&response[..header_end]);
+    let body = &response[header_end + 4..];
+
+    let mut lines = head.lines();
+    let status_line = lines.next().unwrap_or_default();
+    let status: u16 = status_line
+        .split_whitespace()
+        .nth(1)
+        .and_then(|s| s.parse().ok())
+        .with_context(|| format!("Malformed status line: {}", status_line))?;
+    if !(200..300).contains(&status) && status != 304 {
+        anyhow::bail!("{}: {}", status_line, String::from_utf8_lossy(body).trim());
+    }
+
+    let chunked = lines.any(|l| {
+        l.split_once(':').is_some_and(|(k, v)| {
+            k.trim().eq_ignore_ascii_case("transfer-encoding")
+                && v.trim().eq_ignore_ascii_case("chunked")
+        })
+    });
+    if !chunked {
+        return Ok(body.to_vec());
+    }
+
+    let mut decoded = Vec::new();
+    let mut rest = body;
+    loop {
+        let line_end = rest
+            .windows(2)
+            .position(|w| w == b"\r\n")
+            .context("Truncated chunk header")?;
+        let size_str = String::from_utf8_lossy(&rest[..line_end]);
+        // Chunk extensions (";name=value") are allowed after the size
+        let size = usize::from_str_radix(size_str.split(';').next().unwrap_or("").trim(), 16)
+            .with_context(|| format!("Bad chunk size: {}", size_str))?;
+        rest = &rest[line_end + 2..];
+        if size == 0 {
+            break;
+        }
+        decoded.extend_from_slice(rest.get(..size).context("Truncated chunk")?);
+        rest = rest.get(size + 2..).unwrap_or_default();
+    }
+    Ok(decoded)
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "PascalCase")]
+struct ApiContainer {
+    id: String,
+    #[serde(default)]
+    names: Vec<String>,
+    #[serde(default)]
+    image: String,
+    #[serde(default)]
+    labels: Option<HashMap<String, String>>,
+    #[serde(default)]
+    state: String,
+    #[serde(default)]
+    status: String,
+    #[serde(default)]
+    ports: Vec<ApiPort>,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "PascalCase")]
+struct ApiPort {
+    #[serde(rename = "IP")]
+    ip: Option<String>,
+    private_port: u16,
+    // Absent for exposed-but-unpublished ports
+    public_port: Option<u16>,
+    #[serde(rename = "Type")]
+    protocol: String,
+}
+
+impl From<ApiContainer> for ContainerInfo {
+    fn from(c: ApiContainer) -> Self {
+        let labels = c.labels.unwrap_or_default();
+        let ports = c
+            .ports
+            .iter()
+            .filter_map(|p| {
+                Some(PortMapping {
+                    host_ip: p.ip.as_deref().and_then(|ip| ip.parse().ok()),
+                    host_port: p.public_port?,
+                    container_port: p.private_port,
+                    protocol: parse_protocol(&p.protocol)?,
+                })
+            })
+            .collect();
+
+        ContainerInfo {
+            name: c
+                .names
+                .first()
+                .map(|n| n.trim_start_matches('/').to_string())
+                .unwrap_or_else(|| c.id.chars().take(12).collect()),
+            health: parse_health(&c.status),
+            compose_project: compose_label(&labels, "project"),
+            compose_service: compose_label(&labels, "service"),
+            id: c.id,
+            runtime: ContainerRuntime::Docker,
+            endpoint: None,
+            image: c.image,
+            state: c.state,
+            status: c.status,
+            ports,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[cfg(unix)]
+    #[test]
+    fn test_docker_api_fake_socket() {
+        use crate::core::containers::{map_by_host_port, parse_docker_ps};
+        use crate::core::process::Protocol;
+        use std::os::unix::net::UnixListener;
+
+        let body = r#"[{
+            "Id": "4f9a1c2b3d4e5f60718293a4b5c6d7e8f9a0b1c2d3e4f5a6b7c8d9e0f1a2b3c4",
+            "Names": ["/shop-db-1"],
+            "Image": "postgres:16",
+            "Labels": {"com.docker.compose.project": "shop", "com.docker.compose.service": "db"},
+            "State": "running",
+            "Status": "Up 2 hours (healthy)",
+            "Ports": [
+                {"IP": "0.0.0.0", "PrivatePort": 5432, "PublicPort": 15432, "Type": "tcp"},
+                {"IP": "::", "PrivatePort": 5432, "PublicPort": 15432, "Type": "tcp"},
+                {"PrivatePort": 9187, "Type": "tcp"}
+            ]
+        }]"#;
+        // Served chunked, in two pieces, like a daemon speaking HTTP/1.1
+        let (a, b) = body.split_at(body.len() / 2);
+        let response = format!(
+            "HTTP/1.1 200 OK\r\nContent-Type: application/json\r\nTransfer-Encoding: chunked\r\n\r\n{:x}\r\n{}\r\n{:x}\r\n{}\r\n0\r\n\r\n",
+            a.len(),
+            a,
+            b.len(),
+            b
+        );
+
+        let socket =
+            std::env::temp_dir().join(format!("crossport-docker-{}.sock", std::process::id()));
+        let _ = std::fs::remove_file(&socket);
+        let listener = UnixListener::bind(&socket).unwrap();
+        let server = std::thread::spawn(move || {
+            let (mut conn, _) = listener.accept().unwrap();
+            let mut request = [0u8; 1024];
+            let n = conn.read(&mut request).unwrap();
+            conn.write_all(response.as_bytes()).unwrap();
+            String::from_utf8_lossy(&request[..n]).to_string()
+        });
+
+        let containers = DockerClient::new(Endpoint::Unix(socket.clone()))
+            .containers()
+            .unwrap();
+        let request = server.join().unwrap();
+        assert!(request.starts_with("GET /containers/json HTTP/1.0\r\n"));
+
+        assert_eq!(containers.len(), 1);
+        let db = &containers[0];
+        assert_eq!(db.name, "shop-db-1");
+        assert_eq!(db.short_id(), "4f9a1c2b3d4e");
+        assert_eq!(db.health.as_deref(), Some("healthy"));
+        assert_eq!(db.compose_project.as_deref(), Some("shop"));
+        assert_eq!(db.compose_service.as_deref(), Some("db"));
+        // The unpublished port is not a host mapping
+        assert_eq!(db.ports.len(), 2);
+        assert_eq!(db.ports[0].container_port, 5432);
+
+        let map = map_by_host_port(containers);
+        assert!(map.contains_key(&(15432, Protocol::Tcp)));
+        assert!(!map.contains_key(&(15432, Protocol::Udp)));
+
+        // No daemon behind the socket: an error, not a hang or panic
+        std::fs::remove_file(&socket).unwrap();
+        assert!(DockerClient::new(Endpoint::Unix(socket))
+            .containers()
+            .is_err());
+
+        assert_eq!(
+            Endpoint::parse("tcp://127.0.0.1:2375/"),
+            Some(Endpoint::Tcp("127.0.0.1:2375".to_string()))
+        );
+        assert_eq!(Endpoint::parse("ssh://me@host"), None);
+
+        // ...which is left to `docker ps`, as are platforms without unix sockets
+        let cli = parse_docker_ps(
+            "{\"ID\":\"4f9a1c2b3d4e\",\"Image\":\"postgres:16\",\"Labels\":\"com.docker.compose.project=shop\",\"Names\":\"shop-db-1\",\"Ports\":\"0.0.0.0:15432->5432/tcp\",\"State\":\"running\",\"Status\":\"Up 2 hours (healthy)\"}\n",
+        );
+        assert_eq!(cli.len(), 1);
+        assert_eq!(cli[0].runtime, ContainerRuntime::Docker);
+        assert_eq!(cli[0].endpoint, None);
+        assert_eq!(cli[0].health.as_deref(), Some("healthy"));
+        assert_eq!(cli[0].compose_project.as_deref(), Some("shop"));
+        assert_eq!(cli[0].ports[0].host_port, 15432);
+    }
+}
@@ -0,0 +1,872 @@
+use flate2::read::ZlibDecoder;
+use once_cell::sync::Lazy;
+use serde::Serialize;
+use std::collections::HashMap;
+use std::fs;
+use std::io::{BufReader, Read, Seek, SeekFrom};
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+use std::time::{Duration, Instant, SystemTime};
+
+// Reads the repository state straight from the .git directory so that a scan never spawns
+// `git` (which may be missing, slow on large repos, or prompt for credentials).
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct GitInfo {
+    // None when HEAD is detached
+    pub branch: Option<String>,
+    pub head: Option<String>,
+    // Checkout the process runs in; differs from `repo` for linked worktrees
+    pub worktree: PathBuf,
+    pub repo: PathBuf,
+    // Tracked files that differ from HEAD, staged or not; None if the index or HEAD's tree
+    // could not be read
+    pub dirty: Option<bool>,
+}
+
+impl GitInfo {
+    pub fn is_linked_worktree(&self) -> bool {
+        self.worktree != self.repo
+    }
+
+    // Branch name, or the abbreviated commit for a detached HEAD
+    pub fn head_label(&self) -> Option<String> {
+        self.branch
+            .clone()
+            .or_else(|| self.head.as_ref().map(|h| h.chars().take(7).collect()))
+    }
+}
+
+pub fn inspect(cwd: &Path) -> Option<GitInfo> {
+    if cwd.as_os_str().is_empty() {
+        return None;
+    }
+    let worktree = cwd.ancestors().find(|d| d.join(".git").exists())?;
+    let git_dir = resolve_git_dir(worktree)?;
+    // Linked worktrees keep refs and objects in the main repository's .git
+    let common_dir = fs::read_to_string(git_dir.join("commondir"))
+        .ok()
+        .map(|c| git_dir.join(c.trim()))
+        .unwrap_or_else(|| git_dir.clone());
+    let common_dir = common_dir.canonicalize().unwrap_or(common_dir);
+
+    let has_commondir = git_dir.join("commondir").exists();
+    let repo = if common_dir.file_name().is_some_and(|n| n == ".git") {
+        common_dir.parent()?.to_path_buf()
+    } else if !has_commondir {
+        // Submodule (git directory under the superproject's .git/modules/<name>) or
+        // `--separate-git-dir` checkout: the `.git` file sits at the top of the checkout
+        worktree.to_path_buf()
+    } else if let Some(dir) = core_worktree(&common_dir) {
+        // Linked worktree of a submodule
+        dir
+    } else {
+        // Worktree of a bare repository
+        common_dir.clone()
+    };
+
+    let head = fs::read_to_string(git_dir.join("HEAD")).ok()?;
+    let head = head.trim();
+    let (branch, head) = match head.strip_prefix("ref: ") {
+        Some(reference) => (
+            Some(
+                reference
+                    .strip_prefix("refs/heads/")
+                    .unwrap_or(reference)
+                    .to_string(),
+            ),
+            resolve_ref(&common_dir, reference),
+        ),
+        None => (None, Some(head.to_string())),
+    };
+    let dirty = cached_dirty(worktree, &git_dir, &common_dir, head.as_deref());
+
+    Some(GitInfo {
+        branch,
+        head,
+        worktree: worktree.to_path_buf(),
+        repo,
+        dirty,
+    })
+}
+
+// `.git` is a directory, or a file containing `gitdir: <path>` for worktrees and submodules
+fn resolve_git_dir(worktree: &Path) -> Option<PathBuf> {
+    let dot_git = worktree.join(".git");
+    if dot_git.is_dir() {
+        return Some(dot_git);
+    }
+    let content = fs::read_to_string(&dot_git).ok()?;
+    let target = content.trim().strip_prefix("gitdir:")?.trim();
+    Some(worktree.join(target))
+}
+
+// `[core] worktree = <path>`, relative to the git directory
+fn core_worktree(common_dir: &Path) -> Option<PathBuf> {
+    let config = fs::read_to_string(common_dir.join("config")).ok()?;
+    let mut in_core = false;
+    for line in config.lines().map(str::trim) {
+        if line.starts_with('[') {
+            in_core = line.eq_ignore_ascii_case("[core]");
+        } else if in_core {
+            if let Some((key, value)) = line.split_once('=') {
+                if key.trim().eq_ignore_ascii_case("worktree") {
+                    let dir = common_dir.join(value.trim());
+                    return Some(dir.canonicalize().unwrap_or(dir));
+                }
+            }
+        }
+    }
+    None
+}
+
+fn resolve_ref(common_dir: &Path, reference: &str) -> Option<String> {
+    if let Ok(sha) = fs::read_to_string(common_dir.join(reference)) {
+        return Some(sha.trim().to_string());
+    }
+    // Refs that were never updated since `git gc` only live in packed-refs
+    fs::read_to_string(common_dir.join("packed-refs"))
+        .ok()?
+        .lines()
+        .filter(|l| !l.starts_with('#') && !l.starts_with('^'))
+        .find_map(|l| {
+            let (sha, name) = l.split_once(' ')?;
+            (name == reference).then(|| sha.to_string())
+        })
+}
+
+fn hash_len(common_dir: &Path) -> usize {
+    let config = fs::read_to_string(common_dir.join("config")).unwrap_or_default();
+    let sha256 = config.lines().any(|l| {
+        let l = l.trim().replace(' ', "");
+        l == "objectformat=sha256"
+    });
+    if sha256 {
+        32
+    } else {
+        20
+    }
+}
+
+// Dirty state per git directory, kept across captures (the TUI captures every 2 s)
+static DIRTY_CACHE: Lazy<Mutex<HashMap<PathBuf, CachedDirty>>> = Lazy::new(Default::default);
+
+// Editing a tracked file doesn't touch the index, so an unchanged index and HEAD only spare the
+// work for this long
+const DIRTY_CACHE_TTL: Duration = Duration::from_secs(10);
+
+struct CachedDirty {
+    // The index's mtime, size and inode (git replaces it by renaming index.lock), and the
+    // commit HEAD points to
+    index: Option<(SystemTime, u64, u64)>,
+    head: Option<String>,
+    checked: Instant,
+    dirty: Option<bool>,
+}
+
+fn cached_dirty(
+    worktree: &Path,
+    git_dir: &Path,
+    common_dir: &Path,
+    head: Option<&str>,
+) -> Option<bool> {
+    let index = fs::metadata(git_dir.join("index"))
+        .ok()
+        .and_then(|m| Some((m.modified().ok()?, m.len(), inode(&m))));
+    let mut cache = DIRTY_CACHE.lock().unwrap_or_else(|e| e.into_inner());
+    if let Some(cached) = cache.get(git_dir) {
+        if cached.index == index
+            && cached.head.as_deref() == head
+            && cached.checked.elapsed() < DIRTY_CACHE_TTL
+        {
+            return cached.dirty;
+        }
+    }
+    let dirty = is_dirty(worktree, git_dir, common_dir, head);
+    cache.insert(
+        git_dir.to_path_buf(),
+        CachedDirty {
+            index,
+            head: head.map(str::to_string),
+            checked: Instant::now(),
+            dirty,
+        },
+    );
+    dirty
+}
+
+#[cfg(unix)]
+fn inode(meta: &fs::Metadata) -> u64 {
+    use std::os::unix::fs::MetadataExt;
+    meta.ino()
+}
+
+#[cfg(not(unix))]
+fn inode(_meta: &fs::Metadata) -> u64 {
+    0
+}
+
+// What `git status` reports as changes to be committed or not staged, without untracked
+// files: the index is compared with the working tree (stat data only, the same shortcut
+// `git status` takes before hashing), then with HEAD's tree.
+fn is_dirty(
+    worktree: &Path,
+    git_dir: &Path,
+    common_dir: &Path,
+    head: Option<&str>,
+) -> Option<bool> {
+    let hash_len = hash_len(common_dir);
+    let data = match fs::read(git_dir.join("index")) {
+        Ok(data) => data,
+        // A fresh repository without any `git add` has no index
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Some(false),
+        Err(_) => return None,
+    };
+    let (entries, end) = parse_index(&data, hash_len)?;
+    if entries.iter().any(|e| entry_changed(worktree, e)) {
+        return Some(true);
+    }
+
+    // Staged changes. Without a commit (unborn branch) everything in the index is staged.
+    let Some(head) = head else {
+        return Some(!entries.is_empty());
+    };
+    let mut store = ObjectStore::open(common_dir, hash_len);
+    let tree = store.commit_tree(&from_hex(head)?)?;
+    // `git commit` leaves the index's cache tree equal to the committed tree; `git add`
+    // invalidates it
+    if cache_tree_root(&data, end, hash_len).as_ref() == Some(&tree) {
+        return Some(false);
+    }
+    let mut files = HashMap::new();
+    store.flatten_tree(&tree, "", &mut files)?;
+    Some(
+        entries.len() != files.len()
+            || entries
+                .iter()
+                .any(|e| e.stage != 0 || files.get(&e.path) != Some(&(e.mode, e.oid.clone()))),
+    )
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct IndexEntry {
+    pub path: String,
+    pub mtime_secs: u32,
+    pub mtime_nsecs: u32,
+    pub mode: u32,
+    pub size: u32,
+    pub oid: Vec<u8>,
+    // Non-zero for the sides of an unresolved merge conflict
+    pub stage: u16,
+    // assume-unchanged / skip-worktree entries are not compared
+    pub skip: bool,
+}
+
+const ENTRY_FIXED_LEN: usize = 40;
+const FLAG_ASSUME_VALID: u16 = 0x8000;
+const FLAG_EXTENDED: u16 = 0x4000;
+const FLAG_STAGE: u16 = 0x3000;
+const EXT_FLAG_SKIP_WORKTREE: u16 = 0x4000;
+const MODE_GITLINK: u32 = 0o160000;
+
+// Parses the entries of a DIRC index (versions 2-4), returning them with the offset where the
+// extensions start
+fn parse_index(data: &[u8], hash_len: usize) -> Option<(Vec<IndexEntry>, usize)> {
+    let be32 = |pos: usize| -> Option<u32> {
+        data.get(pos..pos + 4)
+            .map(|b| u32::from_be_bytes([b[0], b[1], b[2], b[3]]))
+    };
+    let be16 = |pos: usize| -> Option<u16> {
+        data.get(pos..pos + 2)
+            .map(|b| u16::from_be_bytes([b[0], b[1]]))
+    };
+
+    if data.get(0..4)? != b"DIRC" {
+        return None;
+    }
+    let version = be32(4)?;
+    if !(2..=4).contains(&version) {
+        return None;
+    }
+    let count = be32(8)? as usize;
+
+    let mut entries = Vec::with_capacity(count.min(1 << 16));
+    let mut pos = 12;
+    let mut prev_path: Vec<u8> = Vec::new();
+    for _ in 0..count {
+        let start = pos;
+        let mtime_secs = be32(pos + 8)?;
+        let mtime_nsecs = be32(pos + 12)?;
+        let mode = be32(pos + 24)?;
+        let size = be32(pos + 36)?;
+        let oid = data
+            .get(pos + ENTRY_FIXED_LEN..pos + ENTRY_FIXED_LEN + hash_len)?
+            .to_vec();
+        pos += ENTRY_FIXED_LEN + hash_len;
+        let flags = be16(pos)?;
+        pos += 2;
+        let stage = (flags & FLAG_STAGE) >> 12;
+        let mut skip = flags & FLAG_ASSUME_VALID != 0;
+        if version >= 3 && flags & FLAG_EXTENDED != 0 {
+            skip |= be16(pos)? & EXT_FLAG_SKIP_WORKTREE != 0;
+            pos += 2;
+        }
+
+        let path = if version == 4 {
+            // Prefix-compressed: strip N bytes from the previous path, then append the suffix
+            let (strip, used) = read_varint(data.get(pos..)?)?;
+            pos += used;
+            let suffix_len = data.get(pos..)?.iter().position(|&b| b == 0)?;
+            let keep = prev_path.len().checked_sub(strip)?;
+            let mut path = prev_path[..keep].to_vec();
+            path.extend_from_slice(&data[pos..pos + suffix_len]);
+            pos += suffix_len + 1;
+            path
+        } else {
+            let len = data.get(pos..)?.iter().position(|&b| b == 0)?;
+            let path = data[pos..pos + len].to_vec();
+            // Entries are NUL-padded to a multiple of eight bytes
+            let entry_len = pos + len - start;
+            pos = start + (entry_len + 8) / 8 * 8;
+            path
+        };
+
+        entries.push(IndexEntry {
+            path: String::from_utf8_lossy(&path).to_string(),
+            mtime_secs,
+            mtime_nsecs,
+            mode,
+            size,
+            oid,
+            stage,
+            skip,
+        });
+        prev_path = path;
+    }
+    Some((entries, pos))
+}
+
+// Root of the TREE (cache tree) extension, when it is valid
+fn cache_tree_root(data: &[u8], mut pos: usize, hash_len: usize) -> Option<Vec<u8>> {
+    // The index ends with a checksum of everything before it
+    let end = data.len().checked_sub(hash_len)?;
+    while pos + 8 <= end {
+        let signature = &data[pos..pos + 4];
+        let size = u32::from_be_bytes(data[pos + 4..pos + 8].try_into().ok()?) as usize;
+        let body = data.get(pos + 8..pos + 8 + size)?;
+        if signature == b"TREE" {
+            // "<path>\0<entry count> <subtrees>\n<oid>"; the root's path is empty and an
+            // invalidated tree has an entry count of -1
+            let header_end = body.iter().position(|&b| b == b'\n')?;
+            let header = std::str::from_utf8(body.get(1..header_end)?).ok()?;
+            let count: i64 = header.split(' ').next()?.parse().ok()?;
+            if body[0] != 0 || count < 0 {
+                return None;
+            }
+            return Some(
+                body.get(header_end + 1..header_end + 1 + hash_len)?
+                    .to_vec(),
+            );
+        }
+        pos += 8 + size;
+    }
+    None
+}
+
+// Git's offset varint (as used by index v4 and pack files)
+fn read_varint(data: &[u8]) -> Option<(usize, usize)> {
+    let mut i = 0;
+    let mut byte = *data.get(i)?;
+    let mut value = (byte & 0x7f) as u64;
+    while byte & 0x80 != 0 {
+        i += 1;
+        byte = *data.get(i)?;
+        value = varint_step(value, byte)?;
+    }
+    Some((usize::try_from(value).ok()?, i + 1))
+}
+
+// One more byte of an offset varint; None once the value no longer fits (corrupt data)
+fn varint_step(value: u64, byte: u8) -> Option<u64> {
+    if value >= 1 << 56 {
+        return None;
+    }
+    Some(((value + 1) << 7) | (byte & 0x7f) as u64)
+}
+
+fn entry_changed(worktree: &Path, entry: &IndexEntry) -> bool {
+    if entry.skip || entry.mode == MODE_GITLINK {
+        return false;
+    }
+    let meta = match fs::symlink_metadata(worktree.join(&entry.path)) {
+        Ok(meta) => meta,
+        // Deleted from the working tree
+        Err(_) => return true,
+    };
+    if meta.len() as u32 != entry.size {
+        return true;
+    }
+
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::MetadataExt;
+        if meta.mtime() as u32 != entry.mtime_secs {
+            return true;
+        }
+        // Some filesystems and older git versions record whole seconds only
+        entry.mtime_nsecs != 0 && meta.mtime_nsec() as u32 != entry.mtime_nsecs
+    }
+    #[cfg(not(unix))]
+    {
+        meta.modified()
+            .ok()
+            .and_then(|t| t.duration_since(std::time::UNIX_EPOCH).ok())
+            .is_some_and(|d| d.as_secs() as u32 != entry.mtime_secs)
+    }
+}
+
+fn from_hex(hex: &str) -> Option<Vec<u8>> {
+    if !hex.len().is_multiple_of(2) {
+        return None;
+    }
+    (0..hex.len())
+        .step_by(2)
+        .map(|i| u8::from_str_radix(hex.get(i..i + 2)?, 16).ok())
+        .collect()
+}
+
+fn to_hex(bytes: &[u8]) -> String {
+    bytes.iter().map(|b| format!("{:02x}", b)).collect()
+}
+
+const OBJ_COMMIT: u8 = 1;
+const OBJ_TREE: u8 = 2;
+const OBJ_OFS_DELTA: u8 = 6;
+const OBJ_REF_DELTA: u8 = 7;
+const MODE_TREE: u32 = 0o40000;
+// Deeper delta chains than git itself writes by default (50) are not followed
+const MAX_DELTA_DEPTH: usize = 64;
+
+// Read-only access to loose objects and v2 pack files, just enough to list HEAD's tree
+struct ObjectStore {
+    objects: PathBuf,
+    packs: Vec<Pack>,
+    hash_len: usize,
+}
+
+// Opened once per store; every object read from it seeks the same handle
+struct Pack {
+    file: BufReader<fs::File>,
+    idx: Vec<u8>,
+}
+
+impl ObjectStore {
+    fn open(common_dir: &Path, hash_len: usize) -> Self {
+        let objects = common_dir.join("objects");
+        let packs = fs::read_dir(objects.join("pack"))
+            .into_iter()
+            .flatten()
+            .flatten()
+            .map(|entry| entry.path())
+            .filter(|path| path.extension().is_some_and(|e| e == "idx"))
+            .filter_map(|idx| {
+                Some(Pack {
+                    file: BufReader::new(fs::File::open(idx.with_extension("pack")).ok()?),
+                    idx: fs::read(&idx).ok()?,
+                })
+            })
+            .collect();
+        Self {
+            objects,
+            packs,
+            hash_len,
+        }
+    }
+
+    fn commit_tree(&mut self, commit: &[u8]) -> Option<Vec<u8>> {
+        let (kind, data) = self.read(commit, 0)?;
+        if kind != OBJ_COMMIT {
+            return None;
+        }
+        let first_line = data.split(|&b| b == b'\n').next()?;
+        let tree = std::str::from_utf8(first_line)
+            .ok()?
+            .strip_prefix("tree ")?;
+        from_hex(tree)
+    }
+
+    // Every file of a tree, recursively: path -> (mode, oid)
+    fn flatten_tree(
+        &mut self,
+        tree: &[u8],
+        prefix: &str,
+        files: &mut HashMap<String, (u32, Vec<u8>)>,
+    ) -> Option<()> {
+        let (kind, data) = self.read(tree, 0)?;
+        if kind != OBJ_TREE {
+            return None;
+        }
+        // Entries are "<octal mode> <name>\0<oid>"
+        let mut pos = 0;
+        while pos < data.len() {
+            let space = pos + data[pos..].iter().position(|&b| b == b' ')?;
+            let mode = u32::from_str_radix(std::str::from_utf8(&data[pos..space]).ok()?, 8).ok()?;
+            let nul = space + data[space..].iter().position(|&b| b == 0)?;
+            let name = String::from_utf8_lossy(&data[space + 1..nul]);
+            let oid = data.get(nul + 1..nul + 1 + self.hash_len)?;
+            pos = nul + 1 + self.hash_len;
+
+            let path = if prefix.is_empty() {
+                name.to_string()
+            } else {
+                format!("{}/{}", prefix, name)
+            };
+            if mode == MODE_TREE {
+                self.flatten_tree(oid, &path, files)?;
+            } else {
+                files.insert(path, (mode, oid.to_vec()));
+            }
+        }
+        Some(())
+    }
+
+    fn read(&mut self, oid: &[u8], depth: usize) -> Option<(u8, Vec<u8>)> {
+        if let Some(object) = self.read_loose(oid) {
+            return Some(object);
+        }
+        let (pack, offset) = self
+            .packs
+            .iter()
+            .enumerate()
+            .find_map(|(i, pack)| Some((i, pack_offset(&pack.idx, oid, self.hash_len)?)))?;
+        self.read_packed(pack, offset, depth)
+    }
+
+    fn read_loose(&self, oid: &[u8]) -> Option<(u8, Vec<u8>)> {
+        let hex = to_hex(oid);
+        let raw = fs::File::open(self.objects.join(&hex[..2]).join(&hex[2..])).ok()?;
+        let mut data = Vec::new();
+        ZlibDecoder::new(raw).read_to_end(&mut data).ok()?;
+        // "<type> <size>\0<content>"
+        let nul = data.iter().position(|&b| b == 0)?;
+        let kind = match data[..nul].split(|&b| b == b' ').next()? {
+            b"commit" => OBJ_COMMIT,
+            b"tree" => OBJ_TREE,
+            _ => return None,
+        };
+        Some((kind, data.split_off(nul + 1)))
+    }
+
+    fn read_packed(&mut self, pack: usize, offset: u64, depth: usize) -> Option<(u8, Vec<u8>)> {
+        if depth > MAX_DELTA_DEPTH {
+            return None;
+        }
+        let reader = &mut self.packs[pack].file;
+        reader.seek(SeekFrom::Start(offset)).ok()?;
+        let mut next_byte = || -> Option<u8> {
+            let mut byte = [0u8];
+            reader.read_exact(&mut byte).ok()?;
+            Some(byte[0])
+        };
+
+        // Type in bits 4-6 of the first byte, followed by the (unneeded) inflated size
+        let mut byte = next_byte()?;
+        let kind = (byte >> 4) & 0x7;
+        while byte & 0x80 != 0 {
+            byte = next_byte()?;
+        }
+        match kind {
+            OBJ_OFS_DELTA => {
+                let mut byte = next_byte()?;
+                let mut distance = (byte & 0x7f) as u64;
+                while byte & 0x80 != 0 {
+                    byte = next_byte()?;
+                    distance = varint_step(distance, byte)?;
+                }
+                let delta = inflate(reader)?;
+                let (kind, base) =
+                    self.read_packed(pack, offset.checked_sub(distance)?, depth + 1)?;
+                Some((kind, apply_delta(&base, &delta)?))
+            }
+            OBJ_REF_DELTA => {
+                let mut base_oid = vec![0u8; self.hash_len];
+                reader.read_exact(&mut base_oid).ok()?;
+                let delta = inflate(reader)?;
+                let (kind, base) = self.read(&base_oid, depth + 1)?;
+                Some((kind, apply_delta(&base, &delta)?))
+            }
+            1..=4 => Some((kind, inflate(reader)?)),
+            _ => None,
+        }
+    }
+}
+
+fn inflate(reader: &mut impl Read) -> Option<Vec<u8>> {
+    let mut data = Vec::new();
+    ZlibDecoder::new(reader).read_to_end(&mut data).ok()?;
+    Some(data)
+}
+
+// Looks an object up in a version 2 pack index
+fn pack_offset(idx: &[u8], oid: &[u8], hash_len: usize) -> Option<u64> {
+    let be32 = |pos: usize| -> Option<u32> {
+        idx.get(pos..pos + 4)
+            .map(|b| u32::from_be_bytes([b[0], b[1], b[2], b[3]]))
+    };
+    if idx.get(0..8)? != b"\xfftOc\0\0\0\x02" {
+        return None;
+    }
+    // The fan-out table counts the objects whose first byte is <= its index
+    const FANOUT: usize = 8;
+    let first = *oid.first()? as usize;
+    let mut lo = match first {
+        0 => 0,
+        _ => be32(FANOUT + (first - 1) * 4)? as usize,
+    };
+    let mut hi = be32(FANOUT + first * 4)? as usize;
+    let count = be32(FANOUT + 255 * 4)? as usize;
+
+    let oids = FANOUT + 256 * 4;
+    let found = loop {
+        if lo >= hi {
+            return None;
+        }
+        let mid = (lo + hi) / 2;
+        match idx
+            .get(oids + mid * hash_len..oids + (mid + 1) * hash_len)?
+            .cmp(oid)
+        {
+            std::cmp::Ordering::Less => lo = mid + 1,
+            std::cmp::Ordering::Greater => hi = mid,
+            std::cmp::Ordering::Equal => break mid,
+        }
+    };
+
+    // Object names, then CRC32s, then 31-bit offsets; larger offsets index a 64-bit table
+    let offsets = oids + count * hash_len + count * 4;
+    let offset = be32(offsets + found * 4)?;
+    if offset & 0x8000_0000 == 0 {
+        return Some(offset as u64);
+    }
+    let large = offsets + count * 4 + (offset & 0x7fff_ffff) as usize * 8;
+    idx.get(large..large + 8)
+        .map(|b| u64::from_be_bytes(b.try_into().unwrap_or_default()))
+}
+
+// Applies a pack delta: copy ranges of the base and insert literal bytes
+fn apply_delta(base: &[u8], delta: &[u8]) -> Option<Vec<u8>> {
+    let mut pos = 0;
+    let mut size = || -> Option<u64> {
+        let mut value = 0;
+        let mut shift = 0;
+        loop {
+            // More than 64 bits: corrupt
+            if shift > 63 {
+                return None;
+            }
+            let byte = *delta.get(pos)?;
+            pos += 1;
+            value |= ((byte & 0x7f) as u64) << shift;
+            shift += 7;
+            if byte & 0x80 == 0 {
+                return Some(value);
+            }
+        }
+    };
+    let base_len = size()?;
+    let result_len = usize::try_from(size()?).ok()?;
+    if base_len != base.len() as u64 {
+        return None;
+    }
+
+    // The header comes straight from the pack; don't trust it for the allocation
+    let mut result = Vec::with_capacity(result_len.min(base.len() + delta.len()));
+    while pos < delta.len() {
+        let op = delta[pos];
+        pos += 1;
+        if op & 0x80 != 0 {
+            // Copy: bits 0-3 say which offset bytes follow, bits 4-6 which size bytes
+            let mut offset = 0;
+            let mut len = 0;
+            for i in 0..7 {
+                if op & (1 << i) != 0 {
+                    let byte = *delta.get(pos)? as usize;
+                    pos += 1;
+                    if i < 4 {
+                        offset |= byte << (8 * i);
+                    } else {
+                        len |= byte << (8 * (i - 4));
+                    }
+                }
+            }
+            if len == 0 {
+                len = 0x10000;
+            }
+            result.extend_from_slice(base.get(offset..offset.checked_add(len)?)?);
+        } else if op != 0 {
+            // Insert the next `op` bytes
+            result.extend_from_slice(delta.get(pos..pos + op as usize)?);
+            pos += op as usize;
+        } else {
+            return None;
+        }
+        if result.len() > result_len {
+            return None;
+        }
+    }
+    (result.len() == result_len).then_some(result)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_inspect() {
+        let base = std::env::temp_dir().join(format!("crossport-git-unit-{}", std::process::id()));
+        let main = base.join("app");
+        let wt = base.join("app-feature");
+        let gitdir = main.join(".git");
+        fs::create_dir_all(gitdir.join("worktrees/feature")).unwrap();
+        fs::create_dir_all(main.join("src")).unwrap();
+        fs::create_dir_all(&wt).unwrap();
+        fs::write(gitdir.join("HEAD"), "ref: refs/heads/main\n").unwrap();
+        fs::write(
+            gitdir.join("packed-refs"),
+            "# pack-refs with: peeled\n0123456789abcdef0123456789abcdef01234567 refs/heads/main\n",
+        )
+        .unwrap();
+
+        // Minimal v2 index tracking README
+        let index_for = |size: u32, mtime: u32, oid: [u8; 20]| {
+            let mut index = b"DIRC".to_vec();
+            index.extend_from_slice(&2u32.to_be_bytes());
+            index.extend_from_slice(&1u32.to_be_bytes());
+            let mut entry = vec![0u8; 36];
+            entry[8..12].copy_from_slice(&mtime.to_be_bytes());
+            entry[24..28].copy_from_slice(&0o100644u32.to_be_bytes());
+            entry.extend_from_slice(&size.to_be_bytes());
+            entry.extend_from_slice(&oid);
+            entry.extend_from_slice(&6u16.to_be_bytes()); // flags: name length
+            entry.extend_from_slice(b"README");
+            entry.resize(72, 0);
+            index.extend_from_slice(&entry);
+            index.extend_from_slice(&[0u8; 20]); // checksum
+            index
+        };
+
+        // Replaced through index.lock, as git does
+        let write_index = |data: &[u8]| {
+            fs::write(gitdir.join("index.lock"), data).unwrap();
+            fs::rename(gitdir.join("index.lock"), gitdir.join("index")).unwrap();
+        };
+
+        // The size no longer matches the working tree
+        fs::write(main.join("README"), "changed").unwrap();
+        let index = index_for(3, 0, [0; 20]);
+        let (entries, _) = parse_index(&index, 20).unwrap();
+        assert_eq!(entries.len(), 1);
+        assert_eq!(entries[0].path, "README");
+        assert_eq!(entries[0].size, 3);
+        write_index(&index);
+
+        let info = inspect(&main.join("src")).unwrap();
+        assert_eq!(info.branch.as_deref(), Some("main"));
+        assert_eq!(
+            info.head.as_deref(),
+            Some("0123456789abcdef0123456789abcdef01234567")
+        );
+        assert_eq!(info.dirty, Some(true));
+        assert!(!info.is_linked_worktree());
+
+        // HEAD's commit and tree as loose objects (the names don't need to be real hashes)
+        let write_object = |hex: &str, kind: &str, body: &[u8]| {
+            use std::io::Write;
+            let mut raw = format!("{} {}\0", kind, body.len()).into_bytes();
+            raw.extend_from_slice(body);
+            let mut encoder =
+                flate2::write::ZlibEncoder::new(Vec::new(), flate2::Compression::default());
+            encoder.write_all(&raw).unwrap();
+            let dir = gitdir.join("objects").join(&hex[..2]);
+            fs::create_dir_all(&dir).unwrap();
+            fs::write(dir.join(&hex[2..]), encoder.finish().unwrap()).unwrap();
+        };
+        let tree = "1111111111111111111111111111111111111111";
+        write_object(
+            "0123456789abcdef0123456789abcdef01234567",
+            "commit",
+            format!("tree {}\nauthor a <a@b> 0 +0000\n\nmsg\n", tree).as_bytes(),
+        );
+        let mut tree_body = b"100644 README\0".to_vec();
+        tree_body.extend_from_slice(&[0xaa; 20]);
+        write_object(tree, "tree", &tree_body);
+
+        // The working tree matches the index, but the index differs from HEAD (`git add`ed)
+        let mtime = 1_700_000_000;
+        fs::File::options()
+            .write(true)
+            .open(main.join("README"))
+            .unwrap()
+            .set_modified(std::time::UNIX_EPOCH + std::time::Duration::from_secs(mtime as u64))
+            .unwrap();
+        write_index(&index_for(7, mtime, [0xbb; 20]));
+        assert_eq!(inspect(&main).unwrap().dirty, Some(true));
+
+        // Committed
+        write_index(&index_for(7, mtime, [0xaa; 20]));
+        assert_eq!(inspect(&main).unwrap().dirty, Some(false));
+
+        // Submodule: `.git` file pointing into the superproject's .git/modules
+        let sub = main.join("vendor/lib");
+        let sub_gitdir = gitdir.join("modules/lib");
+        fs::create_dir_all(&sub).unwrap();
+        fs::create_dir_all(&sub_gitdir).unwrap();
+        fs::write(sub.join(".git"), "gitdir: ../../.git/modules/lib\n").unwrap();
+        fs::write(
+            sub_gitdir.join("HEAD"),
+            "89abcdef0123456789abcdef0123456789abcdef\n",
+        )
+        .unwrap();
+        let info = inspect(&sub).unwrap();
+        assert_eq!(info.repo, sub);
+        assert!(!info.is_linked_worktree());
+
+        // Linked worktree on a detached HEAD
+        let wt_gitdir = gitdir.join("worktrees/feature");
+        fs::write(
+            wt.join(".git"),
+            format!("gitdir: {}\n", wt_gitdir.display()),
+        )
+        .unwrap();
+        fs::write(wt_gitdir.join("commondir"), "../..\n").unwrap();
+        fs::write(
+            wt_gitdir.join("HEAD"),
+            "fedcba9876543210fedcba9876543210fedcba98\n",
+        )
+        .unwrap();
+
+        let info = inspect(&wt).unwrap();
+        assert!(info.is_linked_worktree());
+        assert_eq!(info.branch, None);
+        assert_eq!(info.head_label().as_deref(), Some("fedcba9"));
+        assert_eq!(info.dirty, Some(false));
+
+        fs::remove_dir_all(&base).unwrap();
+    }
+
+    #[test]
+    fn test_corrupt_delta() {
+        // base length 3, result length 3: copy 3 bytes from offset 0
+        assert_eq!(apply_delta(b"abc", &[3, 3, 0x90, 3]), Some(b"abc".to_vec()));
+        // Absurd result size: refused without allocating it
+        let mut huge = vec![3];
+        huge.extend_from_slice(&[0xff; 9]);
+        huge.push(0x01);
+        huge.extend_from_slice(&[0x90, 3]);
+        assert_eq!(apply_delta(b"abc", &huge), None);
+        // A size varint longer than 64 bits
+        assert_eq!(apply_delta(b"abc", &[0xff; 16]), None);
+        // Copy past the end of the base
+        assert_eq!(apply_delta(b"abc", &[3, 4, 0x91, 1, 4]), None);
+        assert_eq!(read_varint(&[0xff; 16]), None);
+    }
+}
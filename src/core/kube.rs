@@ -0,0 +1,313 @@
+use serde::Serialize;
+use std::path::Path;
+
+// Recognises the processes that forward cluster ports to the local machine and pulls the
+// target out of their command line:
+//   kubectl -n shop --context staging port-forward svc/api 8080:80 9090:metrics --address 0.0.0.0
+//   oc port-forward pod/web 3000
+//   telepresence intercept api --port 8080:http -n shop
+// k9s forwards from inside its UI, so only its namespace/context flags are known.
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct PortPair {
+    // None for `:80`, where kubectl picks a random local port
+    pub local: Option<u16>,
+    // Numeric or a named container/service port
+    pub remote: String,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct PortForward {
+    // kubectl | oc | k9s | telepresence
+    pub tool: String,
+    pub namespace: Option<String>,
+    pub context: Option<String>,
+    // pod | svc | deployment ... (bare names are pods)
+    pub resource_type: Option<String>,
+    pub resource_name: Option<String>,
+    pub ports: Vec<PortPair>,
+    // --address (defaults to localhost when absent)
+    pub addresses: Vec<String>,
+}
+
+impl PortForward {
+    // "svc/api" as written on the command line
+    pub fn target(&self) -> Option<String> {
+        let name = self.resource_name.as_ref()?;
+        Some(match &self.resource_type {
+            Some(kind) => format!("{}/{}", kind, name),
+            None => name.clone(),
+        })
+    }
+}
+
+// Flags that consume the next argument (unless given as --flag=value)
+const VALUE_FLAGS: &[&str] = &[
+    "-n",
+    "--namespace",
+    "--context",
+    "--address",
+    "--kubeconfig",
+    "--cluster",
+    "--user",
+    "-s",
+    "--server",
+    "--token",
+    "--as",
+    "--as-group",
+    "--request-timeout",
+    "--pod-running-timeout",
+    "-p",
+    "--port",
+    "--env-file",
+    "--mount",
+    "--docker-run",
+];
+
+pub fn parse_port_forward(cmd: &str, args: &[String]) -> Option<PortForward> {
+    let program = args
+        .first()
+        .and_then(|a| Path::new(a).file_name())
+        .map(|s| s.to_string_lossy().to_string())
+        .unwrap_or_else(|| cmd.to_string());
+    // File name rather than stem, which would turn microk8s.kubectl into "microk8s"
+    let program = program
+        .strip_suffix(".exe")
+        .map(str::to_string)
+        .unwrap_or(program);
+    let rest = args.get(1..).unwrap_or_default();
+
+    match program.as_str() {
+        "kubectl" | "oc" => parse_kubectl(&program, rest),
+        // k3s kubectl ... / microk8s kubectl ...
+        "k3s" | "microk8s" => {
+            let sub = rest
+                .iter()
+                .position(|a| a == "kubectl")
+                .map(|i| &rest[i + 1..])
+                .unwrap_or(rest);
+            parse_kubectl("kubectl", sub)
+        }
+        // The snap's alias
+        "microk8s.kubectl" => parse_kubectl("kubectl", rest),
+        "k9s" => {
+            let flags = Flags::parse(rest);
+            Some(PortForward {
+                tool: program,
+                namespace: flags.namespace,
+                context: flags.context,
+                resource_type: None,
+                resource_name: None,
+                ports: Vec::new(),
+                addresses: Vec::new(),
+            })
+        }
+        "telepresence" => parse_telepresence(rest),
+        _ => None,
+    }
+}
+
+fn parse_kubectl(tool: &str, args: &[String]) -> Option<PortForward> {
+    let flags = Flags::parse(args);
+    let mut positionals = flags.positionals.iter();
+    // Global flags may precede the subcommand
+    if positionals.next()? != "port-forward" {
+        return None;
+    }
+    let (resource_type, resource_name) = match positionals.next() {
+        Some(resource) => match resource.split_once('/') {
+            Some((kind, name)) => (Some(kind.to_string()), Some(name.to_string())),
+            None => (None, Some(resource.clone())),
+        },
+        None => (None, None),
+    };
+
+    Some(PortForward {
+        tool: tool.to_string(),
+        namespace: flags.namespace,
+        context: flags.context,
+        resource_type,
+        resource_name,
+        ports: positionals.filter_map(|p| parse_port_pair(p)).collect(),
+        addresses: flags.addresses,
+    })
+}
+
+// Only `telepresence intercept` names a workload; its daemons just carry the connection
+fn parse_telepresence(args: &[String]) -> Option<PortForward> {
+    let flags = Flags::parse(args);
+    let mut positionals = flags.positionals.iter();
+    let intercept = positionals.next().is_some_and(|p| p == "intercept");
+
+    Some(PortForward {
+        tool: "telepresence".to_string(),
+        namespace: flags.namespace,
+        context: flags.context,
+        resource_type: None,
+        resource_name: if intercept {
+            positionals.next().cloned()
+        } else {
+            None
+        },
+        ports: flags
+            .ports
+            .iter()
+            .filter_map(|p| parse_port_pair(p))
+            .collect(),
+        addresses: flags.addresses,
+    })
+}
+
+// `8080:80`, `8080` (same on both ends), `:80` (random local), `8080:http`
+pub fn parse_port_pair(spec: &str) -> Option<PortPair> {
+    match spec.split_once(':') {
+        Some((local, remote)) if !remote.is_empty() => Some(PortPair {
+            local: if local.is_empty() {
+                None
+            } else {
+                Some(local.parse().ok()?)
+            },
+            remote: remote.to_string(),
+        }),
+        Some(_) => None,
+        None => {
+            let port: u16 = spec.parse().ok()?;
+            Some(PortPair {
+                local: Some(port),
+                remote: port.to_string(),
+            })
+        }
+    }
+}
+
+#[derive(Default)]
+struct Flags {
+    namespace: Option<String>,
+    context: Option<String>,
+    addresses: Vec<String>,
+    ports: Vec<String>,
+    positionals: Vec<String>,
+}
+
+impl Flags {
+    fn parse(args: &[String]) -> Self {
+        let mut flags = Flags::default();
+        let mut i = 0;
+        while i < args.len() {
+            let arg = args[i].as_str();
+            i += 1;
+
+            if arg == "--" {
+                // Everything after is passed to a command (telepresence intercept -- cmd)
+                break;
+            }
+            if !arg.starts_with('-') || arg == "-" {
+                flags.positionals.push(arg.to_string());
+                continue;
+            }
+
+            let (name, inline) = match arg.split_once('=') {
+                Some((name, value)) => (name, Some(value.to_string())),
+                // -nshop
+                None if arg.starts_with("-n") && arg.len() > 2 && !arg.starts_with("--") => {
+                    ("-n", Some(arg[2..].to_string()))
+                }
+                None => (arg, None),
+            };
+            if !VALUE_FLAGS.contains(&name) {
+                continue;
+            }
+            let value = match inline {
+                Some(value) => value,
+                None => match args.get(i) {
+                    Some(value) => {
+                        i += 1;
+                        value.clone()
+                    }
+                    None => break,
+                },
+            };
+            match name {
+                "-n" | "--namespace" => flags.namespace = Some(value),
+                "--context" => flags.context = Some(value),
+                "--address" => flags
+                    .addresses
+                    .extend(value.split(',').map(|a| a.trim().to_string())),
+                "-p" | "--port" => flags.ports.push(value),
+                _ => {}
+            }
+        }
+        flags
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_port_forward_parsing() {
+        let argv = |s: &str| s.split_whitespace().map(String::from).collect::<Vec<_>>();
+        let pair = |local: Option<u16>, remote: &str| PortPair {
+            local,
+            remote: remote.to_string(),
+        };
+
+        let args = argv("/usr/local/bin/kubectl --context=staging -n shop port-forward svc/api 8080:80 :9090 3000 8081:metrics --address 0.0.0.0,::");
+        let fwd = parse_port_forward("kubectl", &args).unwrap();
+        assert_eq!(fwd.tool, "kubectl");
+        assert_eq!(fwd.namespace.as_deref(), Some("shop"));
+        assert_eq!(fwd.context.as_deref(), Some("staging"));
+        assert_eq!(fwd.target().as_deref(), Some("svc/api"));
+        assert_eq!(
+            fwd.ports,
+            vec![
+                pair(Some(8080), "80"),
+                pair(None, "9090"),
+                pair(Some(3000), "3000"),
+                pair(Some(8081), "metrics"),
+            ]
+        );
+        assert_eq!(fwd.addresses, vec!["0.0.0.0", "::"]);
+
+        let fwd = parse_port_forward(
+            "oc",
+            &argv("oc port-forward web-7d4b9 5000:5000 --namespace=dev"),
+        )
+        .unwrap();
+        assert_eq!(fwd.tool, "oc");
+        assert_eq!(fwd.resource_type, None);
+        assert_eq!(fwd.resource_name.as_deref(), Some("web-7d4b9"));
+        assert_eq!(fwd.namespace.as_deref(), Some("dev"));
+
+        let fwd = parse_port_forward(
+            "k3s",
+            &argv("k3s kubectl port-forward -nkube-system deploy/traefik 9000"),
+        )
+        .unwrap();
+        assert_eq!(fwd.target().as_deref(), Some("deploy/traefik"));
+        assert_eq!(fwd.namespace.as_deref(), Some("kube-system"));
+
+        let microk8s =
+            argv("/snap/bin/microk8s.kubectl port-forward -n ingress svc/nginx 8443:443");
+        let fwd = parse_port_forward("microk8s.kubectl", &microk8s).unwrap();
+        assert_eq!(fwd.tool, "kubectl");
+        assert_eq!(fwd.target().as_deref(), Some("svc/nginx"));
+        assert_eq!(fwd.ports, vec![pair(Some(8443), "443")]);
+
+        let fwd = parse_port_forward(
+            "telepresence",
+            &argv("telepresence intercept api --port 8080:http -n shop"),
+        )
+        .unwrap();
+        assert_eq!(fwd.target().as_deref(), Some("api"));
+        assert_eq!(fwd.ports, vec![pair(Some(8080), "http")]);
+
+        let fwd = parse_port_forward("k9s", &argv("k9s --context prod")).unwrap();
+        assert_eq!(fwd.context.as_deref(), Some("prod"));
+        assert_eq!(fwd.target(), None);
+
+        assert!(parse_port_forward("kubectl", &argv("kubectl get pods -w")).is_none());
+        assert!(parse_port_forward("node", &argv("node server.js")).is_none());
+    }
+}
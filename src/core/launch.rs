@@ -0,0 +1,282 @@
+use crate::core::process::ProcessInfo;
+use anyhow::{Context, Result};
+use serde::{Serialize, Serializer};
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::process::{Command, Stdio};
+
+// How a process was started, captured before it is killed so that it can be started again.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct LaunchSpec {
+    pub argv: Vec<String>,
+    pub cwd: PathBuf,
+    // None when /proc/<pid>/environ is not readable (another user's process, not Linux);
+    // the process is then restarted with crossport's own environment.
+    // Secrets are masked when serialized (`kill --json`), see `redact`.
+    #[serde(serialize_with = "serialize_env")]
+    pub env: Option<BTreeMap<String, String>>,
+}
+
+impl LaunchSpec {
+    // Reads /proc/<pid> where available, falling back to what the snapshot saw
+    pub fn capture(info: &ProcessInfo) -> Self {
+        let proc_dir = PathBuf::from(format!("/proc/{}", info.pid));
+        let argv = fs::read(proc_dir.join("cmdline"))
+            .ok()
+            .map(|raw| parse_nul_list(&raw))
+            .filter(|argv| !argv.is_empty())
+            .unwrap_or_else(|| info.args.clone());
+        let cwd = fs::read_link(proc_dir.join("cwd")).unwrap_or_else(|_| info.cwd.clone());
+        let env = fs::read(proc_dir.join("environ"))
+            .ok()
+            .map(|raw| parse_environ(&raw));
+
+        Self { argv, cwd, env }
+    }
+
+    // The executable argv[0] names, looked up in the process' PATH. Checked before the process
+    // is killed, so that a restart never leaves nothing running. Processes that rewrote their
+    // title ("next-server (v14.2.3)", "postgres: checkpointer") have lost their original
+    // arguments, so they are refused rather than started bare from /proc/<pid>/exe.
+    pub fn program(&self) -> Result<PathBuf> {
+        let argv0 = self
+            .argv
+            .first()
+            .context("The command line of the process is unknown")?;
+        match self.resolve(argv0) {
+            Some(program) => Ok(program),
+            None => anyhow::bail!(
+                "Cannot restart '{}': it is not an executable (the process may have rewritten its command line); start it again by hand",
+                argv0
+            ),
+        }
+    }
+
+    fn resolve(&self, argv0: &str) -> Option<PathBuf> {
+        if argv0.contains('/') {
+            let path = self.cwd.join(argv0);
+            return is_executable(&path).then_some(path);
+        }
+        let path_var = self
+            .env
+            .as_ref()
+            .and_then(|env| env.get("PATH").cloned())
+            .or_else(|| std::env::var("PATH").ok())?;
+        std::env::split_paths(&path_var)
+            .map(|dir| self.cwd.join(dir).join(argv0))
+            .find(|path| is_executable(path))
+    }
+
+    // Starts the command again, detached from crossport (own process group, no stdio), with
+    // PORT set to `port` if given. Returns the new PID.
+    pub fn spawn(&self, port: Option<u16>) -> Result<u32> {
+        let program = self.program()?;
+        let args = self.argv.get(1..).unwrap_or_default();
+        let mut command = Command::new(&program);
+        command
+            .args(args)
+            .current_dir(&self.cwd)
+            .stdin(Stdio::null())
+            .stdout(Stdio::null())
+            .stderr(Stdio::null());
+        if let Some(env) = &self.env {
+            command.env_clear().envs(env);
+        }
+        if let Some(port) = port {
+            command.env("PORT", port.to_string());
+        }
+        // Keep the terminal's Ctrl+C (and crossport exiting) from reaching it
+        #[cfg(unix)]
+        {
+            use std::os::unix::process::CommandExt;
+            command.process_group(0);
+        }
+
+        let child = command.spawn().with_context(|| {
+            format!(
+                "Failed to start {} in {}",
+                program.display(),
+                self.cwd.display()
+            )
+        })?;
+        Ok(child.id())
+    }
+}
+
+#[cfg(unix)]
+fn is_executable(path: &Path) -> bool {
+    use std::os::unix::fs::PermissionsExt;
+    fs::metadata(path).is_ok_and(|m| m.is_file() && m.permissions().mode() & 0o111 != 0)
+}
+
+#[cfg(not(unix))]
+fn is_executable(path: &Path) -> bool {
+    path.is_file()
+}
+
+// Whole words of a variable name, so that KEYBOARD or GIT_AUTHOR_NAME are left alone
+const SECRET_WORDS: &[&str] = &[
+    "TOKEN",
+    "SECRET",
+    "KEY",
+    "APIKEY",
+    "CREDENTIAL",
+    "CREDENTIALS",
+    "AUTH",
+    "PRIVATE",
+    "COOKIE",
+    "DSN",
+];
+
+// "<redacted>" for variables that look like credentials: secret-sounding names
+// (GITHUB_TOKEN, AWS_SECRET_ACCESS_KEY, PGPASSWORD...) and URLs with a password
+// (postgres://u:p@db/x)
+pub fn redact(key: &str, value: &str) -> String {
+    let upper = key.to_uppercase();
+    let secret_name = upper.contains("PASSWORD")
+        || upper.contains("PASSWD")
+        || upper
+            .split(['_', '-', '.'])
+            .any(|word| SECRET_WORDS.contains(&word));
+    let url_password = value
+        .split_once("://")
+        .and_then(|(_, rest)| rest.split(['/', '?', '#']).next())
+        .is_some_and(|authority| {
+            authority
+                .rsplit_once('@')
+                .is_some_and(|(userinfo, _)| userinfo.contains(':'))
+        });
+    if secret_name || url_password {
+        "<redacted>".to_string()
+    } else {
+        value.to_string()
+    }
+}
+
+fn serialize_env<S: Serializer>(
+    env: &Option<BTreeMap<String, String>>,
+    serializer: S,
+) -> Result<S::Ok, S::Error> {
+    env.as_ref()
+        .map(|env| {
+            env.iter()
+                .map(|(k, v)| (k.clone(), redact(k, v)))
+                .collect::<BTreeMap<_, _>>()
+        })
+        .serialize(serializer)
+}
+
+// /proc/<pid>/cmdline and environ are NUL-separated (and NUL-terminated)
+pub fn parse_nul_list(raw: &[u8]) -> Vec<String> {
+    // Empty arguments are kept; only the final terminator is dropped
+    let raw = raw.strip_suffix(&[0]).unwrap_or(raw);
+    if raw.is_empty() {
+        return Vec::new();
+    }
+    raw.split(|b| *b == 0)
+        .map(|s| String::from_utf8_lossy(s).to_string())
+        .collect()
+}
+
+pub fn parse_environ(raw: &[u8]) -> BTreeMap<String, String> {
+    parse_nul_list(raw)
+        .into_iter()
+        .filter_map(|entry| {
+            let (key, value) = entry.split_once('=')?;
+            Some((key.to_string(), value.to_string()))
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_and_redact() {
+        assert_eq!(
+            parse_nul_list(b"node\0server.js\0\0--port\0"),
+            vec!["node", "server.js", "", "--port"]
+        );
+        assert!(parse_nul_list(b"").is_empty());
+        let env = parse_environ(b"PORT=3000\0DATABASE_URL=postgres://u:p@db/x?a=b\0BROKEN\0");
+        assert_eq!(env.len(), 2);
+        assert_eq!(env["PORT"], "3000");
+        assert_eq!(env["DATABASE_URL"], "postgres://u:p@db/x?a=b");
+
+        // Credentials never reach `kill --json`
+        assert_eq!(redact("PORT", "3000"), "3000");
+        assert_eq!(redact("GITHUB_TOKEN", "ghp_x"), "<redacted>");
+        assert_eq!(redact("aws_secret_access_key", "x"), "<redacted>");
+        assert_eq!(redact("PGPASSWORD", "x"), "<redacted>");
+        assert_eq!(
+            redact("DATABASE_URL", "postgres://u:p@db/x?a=b"),
+            "<redacted>"
+        );
+        assert_eq!(
+            redact("UPSTREAM", "http://api.local/a@b"),
+            "http://api.local/a@b"
+        );
+        // Only whole words count
+        assert_eq!(redact("KEYBOARD", "us"), "us");
+        assert_eq!(redact("GIT_AUTHOR_NAME", "Ann"), "Ann");
+        assert_eq!(redact("AUTHOR", "Ann"), "Ann");
+
+        let json = serde_json::to_value(LaunchSpec {
+            argv: vec!["node".to_string()],
+            cwd: PathBuf::from("/app"),
+            env: Some(env),
+        })
+        .unwrap();
+        assert_eq!(json["env"]["PORT"], "3000");
+        assert_eq!(json["env"]["DATABASE_URL"], "<redacted>");
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_spawn() {
+        let dir = std::env::temp_dir().join(format!("crossport-launch-{}", std::process::id()));
+        fs::create_dir_all(&dir).unwrap();
+        // Relaunch with the captured environment and PORT rewritten
+        let spec = LaunchSpec {
+            argv: vec![
+                "/bin/sh".to_string(),
+                "-c".to_string(),
+                "echo \"$PORT $APP\" > out.tmp && mv out.tmp out".to_string(),
+            ],
+            cwd: dir.clone(),
+            env: Some(parse_environ(b"PATH=/usr/bin:/bin\0PORT=3000\0APP=web\0")),
+        };
+        spec.spawn(Some(3001)).unwrap();
+
+        let out = dir.join("out");
+        for _ in 0..100 {
+            if out.exists() {
+                break;
+            }
+            std::thread::sleep(std::time::Duration::from_millis(20));
+        }
+        assert_eq!(fs::read_to_string(&out).unwrap(), "3001 web\n");
+
+        // A rewritten process title is not a program; the restart is refused up front
+        let err = LaunchSpec {
+            argv: vec!["next-server (v14.2.3)".to_string()],
+            cwd: dir.clone(),
+            env: None,
+        }
+        .program()
+        .unwrap_err();
+        assert!(err
+            .to_string()
+            .contains("Cannot restart 'next-server (v14.2.3)'"));
+        // A bare name is looked up in the captured PATH
+        let bare = LaunchSpec {
+            argv: vec!["sh".to_string()],
+            ..spec.clone()
+        };
+        assert!(bare.program().unwrap().ends_with("bin/sh"));
+
+        fs::remove_dir_all(&dir).unwrap();
+    }
+}
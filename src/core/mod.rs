@@ -1,5 +1,18 @@
+pub mod backend;
+pub mod classify;
+pub mod cmdline;
+pub mod containers;
+pub mod docker;
+pub mod git;
+pub mod kube;
+pub mod launch;
 pub mod process;
+pub mod procfs;
+pub mod project;
+pub mod service;
 pub mod snapshot;
+pub mod ssh;
 
-pub use process::{ProcessInfo, ProcessKind};
+pub use backend::BackendStrategy;
+pub use process::{ProcessInfo, ProcessKind, Protocol};
 pub use snapshot::SystemSnapshot;
@@ -1,5 +1,12 @@
-use serde::Serialize;
+use crate::core::containers::ContainerInfo;
+use crate::core::git::GitInfo;
+use crate::core::kube::PortForward;
+use crate::core::service::ServiceUnit;
+use crate::core::ssh::SshTunnel;
+use serde::{Deserialize, Serialize};
+use std::net::IpAddr;
 use std::path::PathBuf;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
 
 #[derive(Debug, Clone, Serialize)]
 pub struct ProcessInfo {
@@ -7,11 +14,142 @@ pub struct ProcessInfo {
     pub user: String,
     pub uid: Option<u32>,
     pub cmd: String,
+    pub args: Vec<String>,
+    pub exe: Option<PathBuf>,
+    pub display_cmd: String,
     pub cwd: PathBuf,
+    // Seconds since the Unix epoch
+    pub started_at: Option<u64>,
+    // None unless CPU usage was sampled (TUI, `scan --sort cpu`, `crossport <port>`)
+    pub cpu_percent: Option<f32>,
+    pub rss_bytes: u64,
+    pub ppid: Option<u32>,
+    pub pgid: Option<u32>,
+    // Nearest first: parent, grandparent, ... up to (not including) init
+    pub ancestors: Vec<AncestorInfo>,
+    // Repository or workspace root
     pub project_root: Option<PathBuf>,
+    // Nearest package manifest (Cargo.toml, package.json, go.mod...) at or above cwd
+    pub package_root: Option<PathBuf>,
+    pub package_name: Option<String>,
+    pub git: Option<GitInfo>,
     pub container_name: Option<String>,
+    // Set when the port is published by a container
+    pub container: Option<ContainerInfo>,
+    // Set for kubectl / oc / k9s / telepresence forwards
+    pub port_forward: Option<PortForward>,
+    // Set for ssh -L / -D, autossh and sshuttle
+    pub tunnel: Option<SshTunnel>,
+    // systemd unit / launchd job supervising the process
+    pub service: Option<ServiceUnit>,
+    // Why `kill` refuses to touch it, from `[kill] protect` ("port 5432", "user postgres")
+    pub protected: Option<String>,
     pub kind: ProcessKind,
     pub port: u16,
+    pub protocol: Protocol,
+    pub bind_addr: IpAddr,
+    pub family: AddressFamily,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct AncestorInfo {
+    pub pid: u32,
+    pub uid: Option<u32>,
+    pub cmd: String,
+    pub args: Vec<String>,
+    pub display_cmd: String,
+}
+
+// Processes that mark the edge of a dev server's supervision tree: walking past them would
+// reach the user's terminal or the init system.
+const SESSION_BOUNDARIES: &[&str] = &[
+    "init",
+    "systemd",
+    "launchd",
+    "login",
+    "sshd",
+    "tmux",
+    "tmux: server",
+    "screen",
+    "SCREEN",
+    "su",
+    "sudo",
+    "wezterm-gui",
+    "alacritty",
+    "kitty",
+    "gnome-terminal-",
+    "Terminal",
+    "iTerm2",
+];
+const SHELLS: &[&str] = &[
+    "sh", "bash", "zsh", "fish", "dash", "ksh", "tcsh", "nu", "pwsh",
+];
+
+impl AncestorInfo {
+    fn is_session_boundary(&self) -> bool {
+        let name = self.cmd.trim_start_matches('-'); // login shells show up as "-zsh"
+        if self.pid <= 1 || SESSION_BOUNDARIES.contains(&name) {
+            return true;
+        }
+        // `sh -c "next dev"` spawned by npm is part of the wrapper; an interactive shell is not
+        SHELLS.contains(&name) && !self.args.iter().any(|a| a == "-c")
+    }
+}
+
+impl ProcessInfo {
+    // Compose project of the container publishing this port
+    pub fn stack(&self) -> Option<&str> {
+        self.container.as_ref()?.compose_project.as_deref()
+    }
+
+    // "repo" or "repo/package" for subpackages of a monorepo
+    pub fn project_label(&self) -> Option<String> {
+        // Linked worktrees are named after the repository they belong to, not their directory
+        let root_name = self
+            .git
+            .as_ref()
+            .filter(|g| g.is_linked_worktree() && self.project_root.as_ref() == Some(&g.worktree))
+            .map(|g| &g.repo)
+            .or(self.project_root.as_ref())
+            .and_then(|p| p.file_name())
+            .map(|n| n.to_string_lossy().to_string());
+        if self.package_root == self.project_root {
+            return self.package_name.clone().or(root_name);
+        }
+        match (root_name, &self.package_name) {
+            (Some(root), Some(pkg)) => Some(format!("{}/{}", root, pkg)),
+            (root, pkg) => root.or_else(|| pkg.clone()),
+        }
+    }
+
+    pub fn uptime(&self) -> Option<Duration> {
+        let started = UNIX_EPOCH + Duration::from_secs(self.started_at?);
+        SystemTime::now().duration_since(started).ok()
+    }
+
+    // The outermost wrapper (npm run dev, cargo watch, nodemon...) that launched this listener,
+    // i.e. the last ancestor before the chain reaches a shell, terminal or init.
+    pub fn supervisor(&self) -> Option<&AncestorInfo> {
+        self.ancestors
+            .iter()
+            .take_while(|a| !a.is_session_boundary())
+            .last()
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize)]
+pub enum Protocol {
+    Tcp,
+    Udp,
+}
+
+impl Protocol {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Protocol::Tcp => "tcp",
+            Protocol::Udp => "udp",
+        }
+    }
 }
 
 #[derive(Debug, Clone, PartialEq, Serialize)]
@@ -21,18 +159,106 @@ pub enum ProcessKind {
     Dev,
     Docker,
     Kubernetes,
+    Tunnel,
     Other,
+    // Defined by a `[[classify.rules]]` entry; serialized as its bare name
+    #[serde(untagged)]
+    Custom(String),
 }
 
-impl ProcessKind {
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+pub enum AddressFamily {
+    Ipv4,
+    Ipv6,
+}
+
+impl AddressFamily {
+    pub fn of(addr: &IpAddr) -> Self {
+        match addr {
+            IpAddr::V4(_) => AddressFamily::Ipv4,
+            IpAddr::V6(_) => AddressFamily::Ipv6,
+        }
+    }
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            AddressFamily::Ipv4 => "ipv4",
+            AddressFamily::Ipv6 => "ipv6",
+        }
+    }
+}
+
+// How reachable a listener is, ordered from least to most exposed. Lan covers private and
+// link-local addresses; a wildcard or public address is reachable from anywhere.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Exposure {
+    Loopback,
+    Lan,
+    #[serde(rename = "all")]
+    AllInterfaces,
+}
+
+impl Exposure {
+    pub fn of(addr: &IpAddr) -> Self {
+        // ::ffff:127.0.0.1 is still loopback
+        let addr = match addr {
+            IpAddr::V6(v6) => v6
+                .to_ipv4_mapped()
+                .map(IpAddr::V4)
+                .unwrap_or(IpAddr::V6(*v6)),
+            v4 => *v4,
+        };
+        let private = match addr {
+            IpAddr::V4(v4) => v4.is_private() || v4.is_link_local(),
+            // fc00::/7 (unique local) and fe80::/10 (link-local)
+            IpAddr::V6(v6) => {
+                let first = v6.segments()[0];
+                (first & 0xfe00) == 0xfc00 || (first & 0xffc0) == 0xfe80
+            }
+        };
+        if addr.is_loopback() {
+            Exposure::Loopback
+        } else if private {
+            Exposure::Lan
+        } else {
+            Exposure::AllInterfaces
+        }
+    }
+
     pub fn as_str(&self) -> &'static str {
+        match self {
+            Exposure::Loopback => "loopback",
+            Exposure::Lan => "lan",
+            Exposure::AllInterfaces => "all",
+        }
+    }
+}
+
+impl ProcessKind {
+    pub fn as_str(&self) -> &str {
         match self {
             ProcessKind::System => "system",
             ProcessKind::Brew => "brew",
             ProcessKind::Dev => "dev",
             ProcessKind::Docker => "docker",
             ProcessKind::Kubernetes => "k8s",
+            ProcessKind::Tunnel => "tunnel",
             ProcessKind::Other => "other",
+            ProcessKind::Custom(name) => name,
+        }
+    }
+
+    pub fn from_name(name: &str) -> Self {
+        match name.to_lowercase().as_str() {
+            "system" => ProcessKind::System,
+            "brew" => ProcessKind::Brew,
+            "dev" => ProcessKind::Dev,
+            "docker" => ProcessKind::Docker,
+            "k8s" | "kubernetes" => ProcessKind::Kubernetes,
+            "tunnel" => ProcessKind::Tunnel,
+            "other" => ProcessKind::Other,
+            _ => ProcessKind::Custom(name.to_string()),
         }
     }
 }
@@ -0,0 +1,154 @@
+use crate::core::backend::Listener;
+use crate::core::process::Protocol;
+use anyhow::{Context, Result};
+use std::collections::HashMap;
+use std::fs;
+use std::net::{IpAddr, Ipv4Addr, Ipv6Addr};
+
+// Native Linux backend: reads /proc/net/{tcp,udp}{,6} directly instead of shelling out to lsof.
+
+const TCP_LISTEN: &str = "0A";
+const UDP_UNCONN: &str = "07";
+
+const TABLES: [(&str, Protocol); 4] = [
+    ("/proc/net/tcp", Protocol::Tcp),
+    ("/proc/net/tcp6", Protocol::Tcp),
+    ("/proc/net/udp", Protocol::Udp),
+    ("/proc/net/udp6", Protocol::Udp),
+];
+
+pub fn scan_ports() -> Result<Vec<Listener>> {
+    let mut sockets = Vec::new();
+    let mut read_any = false;
+
+    for (table, protocol) in TABLES {
+        // v6 tables are missing when IPv6 is disabled, so only fail if nothing is readable
+        if let Ok(content) = fs::read_to_string(table) {
+            read_any = true;
+            sockets.extend(
+                parse_proc_net(&content, protocol)
+                    .into_iter()
+                    .map(|(addr, port, inode)| (addr, port, inode, protocol)),
+            );
+        }
+    }
+
+    if !read_any {
+        anyhow::bail!("/proc/net/tcp is not readable");
+    }
+
+    let inode_to_pid = socket_inodes_by_pid().context("Failed to read /proc")?;
+
+    let mut results = Vec::new();
+    for (addr, port, inode, protocol) in sockets {
+        if let Some(&pid) = inode_to_pid.get(&inode) {
+            let listener = Listener {
+                pid,
+                port,
+                protocol,
+                addr,
+            };
+            if !results.contains(&listener) {
+                results.push(listener);
+            }
+        }
+    }
+    Ok(results)
+}
+
+// Returns (address, port, socket inode) for every listening row of a /proc/net table:
+// LISTEN for TCP, unconnected bound sockets for UDP.
+pub fn parse_proc_net(content: &str, protocol: Protocol) -> Vec<(IpAddr, u16, u64)> {
+    let mut results = Vec::new();
+
+    // Header: sl local_address rem_address st tx_queue:rx_queue tr:tm->when retrnsmt uid timeout inode
+    for line in content.lines().skip(1) {
+        let parts: Vec<&str> = line.split_whitespace().collect();
+        if parts.len() < 10 {
+            continue;
+        }
+
+        let listening = match protocol {
+            Protocol::Tcp => parts[3] == TCP_LISTEN,
+            // Connected UDP sockets are clients; only a zero remote port means "bound and waiting"
+            Protocol::Udp => parts[3] == UDP_UNCONN && parts[2].ends_with(":0000"),
+        };
+        if !listening {
+            continue;
+        }
+
+        // local_address: 0100007F:0BB8 (hex address, hex port)
+        let (addr, port) = match parts[1].split_once(':') {
+            Some((addr, port)) => (parse_hex_addr(addr), u16::from_str_radix(port, 16).ok()),
+            None => continue,
+        };
+        let inode = parts[9].parse::<u64>().ok();
+
+        if let (Some(addr), Some(port), Some(inode)) = (addr, port, inode) {
+            if inode != 0 {
+                results.push((addr, port, inode));
+            }
+        }
+    }
+    results
+}
+
+// The kernel prints addresses as native-endian 32-bit words: 0100007F is 127.0.0.1 on
+// little-endian hosts, and IPv6 addresses are four such words.
+fn parse_hex_addr(hex: &str) -> Option<IpAddr> {
+    let mut bytes = Vec::with_capacity(16);
+    for i in (0..hex.len()).step_by(8) {
+        let word = u32::from_str_radix(hex.get(i..i + 8)?, 16).ok()?;
+        bytes.extend_from_slice(&word.to_ne_bytes());
+    }
+
+    match bytes.len() {
+        4 => Some(IpAddr::V4(Ipv4Addr::new(
+            bytes[0], bytes[1], bytes[2], bytes[3],
+        ))),
+        16 => {
+            let octets: [u8; 16] = bytes.try_into().ok()?;
+            Some(IpAddr::V6(Ipv6Addr::from(octets)).to_canonical())
+        }
+        _ => None,
+    }
+}
+
+// Maps socket inodes to the owning PID by following /proc/<pid>/fd/* links ("socket:[12345]").
+// Processes of other users are unreadable without privileges and are silently skipped, like lsof.
+fn socket_inodes_by_pid() -> Result<HashMap<u64, u32>> {
+    let mut map = HashMap::new();
+
+    for entry in fs::read_dir("/proc")?.flatten() {
+        let pid = match entry
+            .file_name()
+            .to_str()
+            .and_then(|s| s.parse::<u32>().ok())
+        {
+            Some(pid) => pid,
+            None => continue,
+        };
+
+        let fds = match fs::read_dir(entry.path().join("fd")) {
+            Ok(fds) => fds,
+            Err(_) => continue,
+        };
+
+        for fd in fds.flatten() {
+            if let Ok(target) = fs::read_link(fd.path()) {
+                if let Some(inode) = parse_socket_link(&target.to_string_lossy()) {
+                    map.entry(inode).or_insert(pid);
+                }
+            }
+        }
+    }
+    Ok(map)
+}
+
+fn parse_socket_link(target: &str) -> Option<u64> {
+    target
+        .strip_prefix("socket:[")?
+        .strip_suffix(']')?
+        .parse::<u64>()
+        .ok()
+}
@@ -0,0 +1,131 @@
+use std::fs;
+use std::path::{Path, PathBuf};
+
+// Works out which project a working directory belongs to. `root` is the repository (or
+// workspace) root; `package_*` is the nearest manifest, which differs from the root inside
+// monorepo subpackages.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Project {
+    pub root: Option<PathBuf>,
+    pub package_root: Option<PathBuf>,
+    pub package_name: Option<String>,
+}
+
+struct Manifest {
+    // Names a package living in this directory
+    package: Option<String>,
+    // Groups packages below it (Cargo/npm workspaces, go.work)
+    workspace: bool,
+}
+
+pub fn detect(cwd: &Path) -> Project {
+    let mut project = Project::default();
+    if cwd.as_os_str().is_empty() {
+        return project;
+    }
+
+    let mut workspace_root = None;
+    for dir in cwd.ancestors() {
+        let manifest = read_manifest(dir);
+        if project.package_root.is_none() {
+            if let Some(name) = manifest.package {
+                project.package_root = Some(dir.to_path_buf());
+                project.package_name = Some(name);
+            }
+        }
+        if manifest.workspace && workspace_root.is_none() {
+            workspace_root = Some(dir.to_path_buf());
+        }
+        // A repository or an explicit crossport.toml bounds the search
+        if dir.join(".git").exists() || dir.join("crossport.toml").is_file() {
+            project.root = Some(dir.to_path_buf());
+            break;
+        }
+    }
+
+    if project.root.is_none() {
+        project.root = workspace_root.or_else(|| project.package_root.clone());
+    }
+    project
+}
+
+fn read_manifest(dir: &Path) -> Manifest {
+    let mut manifest = Manifest {
+        package: None,
+        workspace: dir.join("go.work").is_file() || dir.join("pnpm-workspace.yaml").is_file(),
+    };
+
+    if let Some(cargo) = read_toml(&dir.join("Cargo.toml")) {
+        manifest.workspace |= cargo.contains_key("workspace");
+        manifest.package = toml_str(&cargo, &["package", "name"]);
+    }
+    if let Some(pkg) = read_json(&dir.join("package.json")) {
+        manifest.workspace |= pkg.get("workspaces").is_some();
+        manifest.package = manifest
+            .package
+            .or_else(|| pkg.get("name").and_then(|n| n.as_str()).map(String::from));
+    }
+    if manifest.package.is_none() {
+        manifest.package = read_go_module(&dir.join("go.mod"))
+            .or_else(|| {
+                read_toml(&dir.join("pyproject.toml")).and_then(|py| {
+                    toml_str(&py, &["project", "name"])
+                        .or_else(|| toml_str(&py, &["tool", "poetry", "name"]))
+                })
+            })
+            .or_else(|| {
+                dir.join("Gemfile")
+                    .is_file()
+                    .then(|| gemspec_name(dir).unwrap_or_else(|| dir_name(dir)))
+            })
+            .or_else(|| {
+                read_toml(&dir.join("crossport.toml"))
+                    .map(|c| toml_str(&c, &["project", "name"]).unwrap_or_else(|| dir_name(dir)))
+            });
+    }
+    manifest
+}
+
+fn read_toml(path: &Path) -> Option<toml::Table> {
+    fs::read_to_string(path).ok()?.parse().ok()
+}
+
+fn read_json(path: &Path) -> Option<serde_json::Value> {
+    serde_json::from_str(&fs::read_to_string(path).ok()?).ok()
+}
+
+fn toml_str(table: &toml::Table, keys: &[&str]) -> Option<String> {
+    let (last, path) = keys.split_last()?;
+    let mut current = table;
+    for key in path {
+        current = current.get(*key)?.as_table()?;
+    }
+    current.get(*last)?.as_str().map(String::from)
+}
+
+// `module github.com/acme/api` -> "api"
+fn read_go_module(path: &Path) -> Option<String> {
+    let content = fs::read_to_string(path).ok()?;
+    let module = content
+        .lines()
+        .find_map(|l| l.trim().strip_prefix("module "))?
+        .trim()
+        .trim_matches('"');
+    module.rsplit('/').next().map(String::from)
+}
+
+fn gemspec_name(dir: &Path) -> Option<String> {
+    fs::read_dir(dir).ok()?.flatten().find_map(|entry| {
+        let path = entry.path();
+        if path.extension()? != "gemspec" {
+            return None;
+        }
+        path.file_stem().map(|s| s.to_string_lossy().to_string())
+    })
+}
+
+fn dir_name(dir: &Path) -> String {
+    dir.file_name()
+        .map(|n| n.to_string_lossy().to_string())
+        .unwrap_or_else(|| dir.display().to_string())
+}
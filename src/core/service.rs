@@ -0,0 +1,231 @@
+use anyhow::{Context, Result};
+use serde::Serialize;
+use std::collections::HashMap;
+use std::fs;
+use std::process::Command;
+
+// Services supervised by systemd or launchd are restarted as soon as they die, so the useful
+// action is to stop the unit / job rather than signal its process.
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+pub enum ServiceManager {
+    Systemd,
+    Launchd,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+pub enum ServiceScope {
+    System,
+    User,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct ServiceUnit {
+    pub manager: ServiceManager,
+    pub scope: ServiceScope,
+    // postgresql@16-main.service, homebrew.mxcl.redis ...
+    pub name: String,
+}
+
+impl ServiceUnit {
+    pub fn manager_name(&self) -> &'static str {
+        match self.manager {
+            ServiceManager::Systemd => "systemd",
+            ServiceManager::Launchd => "launchd",
+        }
+    }
+
+    pub fn scope_name(&self) -> &'static str {
+        match self.scope {
+            ServiceScope::System => "system",
+            ServiceScope::User => "user",
+        }
+    }
+
+    // The command that stops the service for good (until it is started again)
+    pub fn stop_command(&self) -> Vec<String> {
+        let mut command = match (self.manager, self.scope) {
+            (ServiceManager::Systemd, ServiceScope::System) => vec!["systemctl", "stop"],
+            (ServiceManager::Systemd, ServiceScope::User) => vec!["systemctl", "--user", "stop"],
+            (ServiceManager::Launchd, _) => vec!["launchctl", "bootout"],
+        }
+        .into_iter()
+        .map(String::from)
+        .collect::<Vec<_>>();
+        command.push(match self.manager {
+            ServiceManager::Systemd => self.name.clone(),
+            ServiceManager::Launchd => format!("{}/{}", launchd_domain(self.scope), self.name),
+        });
+        command
+    }
+
+    pub fn stop(&self) -> Result<()> {
+        let command = self.stop_command();
+        let output = Command::new(&command[0])
+            .args(&command[1..])
+            .output()
+            .with_context(|| format!("Failed to execute {}", command[0]))?;
+        if !output.status.success() {
+            anyhow::bail!(
+                "{} failed: {}",
+                command.join(" "),
+                String::from_utf8_lossy(&output.stderr).trim()
+            );
+        }
+        Ok(())
+    }
+}
+
+#[cfg(unix)]
+fn launchd_domain(scope: ServiceScope) -> String {
+    match scope {
+        ServiceScope::System => "system".to_string(),
+        ServiceScope::User => format!("gui/{}", unsafe { libc::getuid() }),
+    }
+}
+
+#[cfg(not(unix))]
+fn launchd_domain(_scope: ServiceScope) -> String {
+    "system".to_string()
+}
+
+// The systemd unit (from /proc/<pid>/cgroup) or launchd job owning `pid`
+pub fn owning_unit(pid: u32, launchd_jobs: &HashMap<u32, ServiceUnit>) -> Option<ServiceUnit> {
+    fs::read_to_string(format!("/proc/{}/cgroup", pid))
+        .ok()
+        .and_then(|content| parse_cgroup(&content))
+        .or_else(|| launchd_jobs.get(&pid).cloned())
+}
+
+// cgroup v2 has a single "0::/path" line; on v1 the path of the name=systemd hierarchy is used.
+//   0::/system.slice/postgresql@16-main.service
+//   0::/user.slice/user-1000.slice/user@1000.service/app.slice/redis.service
+// Session and transient scopes (terminals, tmux, containers) are not services.
+pub fn parse_cgroup(content: &str) -> Option<ServiceUnit> {
+    let path = content
+        .lines()
+        .filter_map(|l| {
+            let mut fields = l.splitn(3, ':');
+            let (_, controllers, path) = (fields.next()?, fields.next()?, fields.next()?);
+            Some((controllers, path))
+        })
+        .find(|(controllers, _)| controllers.is_empty() || *controllers == "name=systemd")
+        .map(|(_, path)| path)?;
+
+    let segments: Vec<&str> = path.split('/').collect();
+    // user@<uid>.service is the per-user manager; the units below it belong to that user
+    let user_manager = segments
+        .iter()
+        .position(|s| s.starts_with("user@") && s.ends_with(".service"));
+    let (scope, units) = match user_manager {
+        Some(i) => (ServiceScope::User, &segments[i + 1..]),
+        None => (ServiceScope::System, &segments[..]),
+    };
+    let name = units.iter().rev().find(|s| s.ends_with(".service"))?;
+
+    Some(ServiceUnit {
+        manager: ServiceManager::Systemd,
+        scope,
+        name: name.to_string(),
+    })
+}
+
+// Running jobs of the caller's launchd domain (the system domain when run as root)
+pub fn launchd_jobs() -> HashMap<u32, ServiceUnit> {
+    if !cfg!(target_os = "macos") {
+        return HashMap::new();
+    }
+    #[cfg(unix)]
+    let scope = if unsafe { libc::getuid() } == 0 {
+        ServiceScope::System
+    } else {
+        ServiceScope::User
+    };
+    #[cfg(not(unix))]
+    let scope = ServiceScope::System;
+
+    Command::new("launchctl")
+        .arg("list")
+        .output()
+        .ok()
+        .filter(|o| o.status.success())
+        .map(|o| parse_launchctl_list(&String::from_utf8_lossy(&o.stdout), scope))
+        .unwrap_or_default()
+}
+
+// `launchctl list`: "PID\tStatus\tLabel", with "-" for jobs that are not running
+pub fn parse_launchctl_list(output: &str, scope: ServiceScope) -> HashMap<u32, ServiceUnit> {
+    output
+        .lines()
+        .skip(1)
+        .filter_map(|l| {
+            let mut fields = l.split('\t');
+            let pid: u32 = fields.next()?.trim().parse().ok()?;
+            let label = fields.nth(1)?.trim();
+            // Per-application instances (e.g. "application.com.apple.Terminal.123") are not jobs
+            // that can be booted out
+            if label.is_empty() || label.starts_with("application.") {
+                return None;
+            }
+            Some((
+                pid,
+                ServiceUnit {
+                    manager: ServiceManager::Launchd,
+                    scope,
+                    name: label.to_string(),
+                },
+            ))
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_service_unit_detection() {
+        let unit = parse_cgroup("0::/system.slice/postgresql@16-main.service\n").unwrap();
+        assert_eq!(unit.name, "postgresql@16-main.service");
+        assert_eq!(unit.scope, ServiceScope::System);
+        assert_eq!(
+            unit.stop_command(),
+            vec!["systemctl", "stop", "postgresql@16-main.service"]
+        );
+
+        let unit = parse_cgroup(
+            "0::/user.slice/user-1000.slice/user@1000.service/app.slice/redis.service\n",
+        )
+        .unwrap();
+        assert_eq!(unit.name, "redis.service");
+        assert_eq!(unit.scope, ServiceScope::User);
+        assert_eq!(
+            unit.stop_command(),
+            vec!["systemctl", "--user", "stop", "redis.service"]
+        );
+
+        // cgroup v1: only the name=systemd hierarchy names units
+        let v1 =
+            "4:memory:/system.slice/nginx.service\n1:name=systemd:/system.slice/nginx.service\n";
+        assert_eq!(parse_cgroup(v1).unwrap().name, "nginx.service");
+
+        // Terminal sessions, the user manager itself, containers and non-systemd hosts
+        for cgroup in [
+            "0::/user.slice/user-1000.slice/session-3.scope",
+            "0::/user.slice/user-1000.slice/user@1000.service/init.scope",
+            "0::/user.slice/user-1000.slice/user@1000.service/app.slice/app-org.gnome.Terminal.slice/vte-spawn-1.scope",
+            "0::/system.slice/docker-4f1c0a9e.scope",
+            "0::/",
+        ] {
+            assert!(parse_cgroup(cgroup).is_none(), "{}", cgroup);
+        }
+
+        let jobs = parse_launchctl_list(
+            "PID\tStatus\tLabel\n412\t0\thomebrew.mxcl.postgresql@14\n-\t0\tcom.apple.Safari\n733\t0\tapplication.com.apple.Terminal.1\n",
+            ServiceScope::User,
+        );
+        assert_eq!(jobs.len(), 1);
+        assert_eq!(jobs[&412].name, "homebrew.mxcl.postgresql@14");
+        assert_eq!(jobs[&412].stop_command()[..2], ["launchctl", "bootout"]);
+    }
+}
@@ -1,8 +1,16 @@
-use crate::core::process::{ProcessInfo, ProcessKind};
+use crate::core::backend::{Listener, PortBackend};
+use crate::core::classify::{Classifier, ClassifyContext};
+use crate::core::cmdline;
+use crate::core::containers::{self, ContainerInfo};
+use crate::core::git::{self, GitInfo};
+use crate::core::kube;
+use crate::core::process::{AddressFamily, AncestorInfo, ProcessInfo, Protocol};
+use crate::core::project;
+use crate::core::service::{self, ServiceUnit};
+use crate::core::ssh;
 use anyhow::{Context, Result};
 use std::collections::HashMap;
 use std::path::PathBuf;
-use std::process::Command;
 use sysinfo::{Pid, System, Users};
 
 pub struct SystemSnapshot {
@@ -10,24 +18,46 @@ pub struct SystemSnapshot {
 }
 
 impl SystemSnapshot {
-    pub fn capture() -> Result<Self> {
-        let mut sys = System::new_all();
-        sys.refresh_all();
+    // `sys` comes from `new_system`, or is kept and refreshed between captures (TUI). CPU usage
+    // is only reported when `measure_cpu` is set and `sys` has been refreshed at least once.
+    pub fn capture_with<B: PortBackend + ?Sized>(
+        backend: &B,
+        classifier: &Classifier,
+        sys: &System,
+        measure_cpu: bool,
+    ) -> Result<Self> {
         let users = Users::new_with_refreshed_list();
 
         // 1. Get all listening ports and PIDs
-        let ports_pids = get_all_listening_ports()?;
+        let listeners = backend
+            .listening_ports()
+            .with_context(|| format!("Failed to list listening ports via {}", backend.name()))?;
 
         // 2. Get Docker info
-        let docker_map = get_docker_containers().unwrap_or_default();
+        let docker_map = containers::container_map();
+        let launchd_jobs = service::launchd_jobs();
 
         let mut processes_by_port = HashMap::new();
+        // Reading the git index is the costly part; listeners of one app share a cwd
+        let mut git_cache = HashMap::new();
 
         // 3. Enrich
-        for (pid_val, port) in ports_pids {
-            if let Some(info) = enrich_process_info(&sys, &users, pid_val, port, &docker_map) {
+        for listener in listeners {
+            if let Some(mut info) = enrich_process_info(
+                sys,
+                &users,
+                &listener,
+                &docker_map,
+                &launchd_jobs,
+                classifier,
+                &mut git_cache,
+            ) {
+                // Without a previous sample sysinfo reports 0%, which would be misleading
+                if !measure_cpu {
+                    info.cpu_percent = None;
+                }
                 processes_by_port
-                    .entry(port)
+                    .entry(listener.port)
                     .or_insert_with(Vec::new)
                     .push(info);
             }
@@ -36,6 +66,17 @@ impl SystemSnapshot {
         Ok(Self { processes_by_port })
     }
 
+    // CPU usage is measured between two refreshes, which costs MINIMUM_CPU_UPDATE_INTERVAL
+    // (~200 ms); only pay for it where CPU is shown or sorted on
+    pub fn new_system(measure_cpu: bool) -> System {
+        let mut sys = System::new_all();
+        if measure_cpu {
+            std::thread::sleep(sysinfo::MINIMUM_CPU_UPDATE_INTERVAL);
+            sys.refresh_processes();
+        }
+        sys
+    }
+
     pub fn get_process_info(&self, port: u16) -> Vec<ProcessInfo> {
         self.processes_by_port
             .get(&port)
@@ -47,10 +88,14 @@ impl SystemSnapshot {
 fn enrich_process_info(
     sys: &System,
     users: &Users,
-    pid_val: u32,
-    port: u16,
-    docker_map: &HashMap<u16, String>,
+    listener: &Listener,
+    docker_map: &HashMap<(u16, Protocol), ContainerInfo>,
+    launchd_jobs: &HashMap<u32, ServiceUnit>,
+    classifier: &Classifier,
+    git_cache: &mut HashMap<PathBuf, Option<GitInfo>>,
 ) -> Option<ProcessInfo> {
+    let pid_val = listener.pid;
+    let port = listener.port;
     let pid = Pid::from(pid_val as usize);
     let process = sys.process(pid)?;
 
@@ -64,212 +109,126 @@ fn enrich_process_info(
         "unknown".to_string()
     };
 
-    // TODO: Extract numeric UID from sysinfo for proper permission checks
+    // Numeric UID is what permission checks compare against (names can be ambiguous)
+    #[cfg(unix)]
+    let uid_val = process.user_id().map(|uid| **uid);
+    #[cfg(not(unix))]
     let uid_val = None;
 
     let cmd = process.name().to_string();
+    let args = process.cmd().to_vec();
+    let exe = process.exe().map(|p| p.to_path_buf());
+    let display_cmd = cmdline::display_command(&cmd, &args);
     let cwd = process.cwd().map(|p| p.to_path_buf()).unwrap_or_default();
+    let ppid = process.parent().map(|p| p.as_u32());
+    let ancestors = collect_ancestors(sys, process);
 
-    // Determine kind
-    let mut kind = determine_kind(&cmd, &cwd, &user);
+    #[cfg(unix)]
+    let pgid = match unsafe { libc::getpgid(pid_val as libc::pid_t) } {
+        -1 => None,
+        pgid => Some(pgid as u32),
+    };
+    #[cfg(not(unix))]
+    let pgid = None;
 
-    // Try to find project root (git)
-    let project_root = find_git_root(&cwd);
+    // sysinfo reports 0 when the start time is unknown
+    let started_at = Some(process.start_time()).filter(|&t| t > 0);
 
-    let mut container_name = None;
+    let project = project::detect(&cwd);
+    let git = git_cache
+        .entry(cwd.clone())
+        .or_insert_with(|| git::inspect(&cwd))
+        .clone();
 
-    // Check for Kubernetes
-    if cmd == "kubectl" || cmd == "k3s" {
-        // Try to parse full command line arguments to find target
-        if let Some(args) = process.cmd().get(0..) {
-            // Try to find "port-forward" and the service/pod name
-            // args usually: ["kubectl", "port-forward", "svc/my-api", "8080:80"]
-            if args.iter().any(|arg| arg == "port-forward") {
-                kind = ProcessKind::Kubernetes;
-                // Heuristic: Find the arg that doesn't start with "-" and isn't "port-forward" or "kubectl"
-                if let Some(target) = args
-                    .iter()
-                    .skip_while(|&a| a != "port-forward")
-                    .skip(1)
-                    .find(|&a| !a.starts_with('-') && !a.contains(':'))
-                {
-                    container_name = Some(target.clone());
-                }
-            }
-        }
-    }
+    // Kubernetes port-forward: remember the forwarded resource
+    let port_forward = kube::parse_port_forward(&cmd, &args);
+    let mut container_name = port_forward.as_ref().and_then(|f| f.target());
+    let tunnel = ssh::parse_tunnel(&cmd, &args);
+    let service = service::owning_unit(pid_val, launchd_jobs);
 
     // Check Docker map
-    if let Some(name) = docker_map.get(&port) {
-        container_name = Some(name.clone());
-        kind = ProcessKind::Docker;
+    let container = docker_map.get(&(port, listener.protocol)).cloned();
+    if let Some(c) = &container {
+        container_name = Some(c.name.clone());
     }
 
+    let kind = classifier.classify(&ClassifyContext {
+        cmd: &cmd,
+        args: &args,
+        exe: exe.as_deref(),
+        cwd: &cwd,
+        user: &user,
+        port,
+        has_container: container.is_some(),
+    });
+
     Some(ProcessInfo {
         pid: pid_val,
         user,
         uid: uid_val,
         cmd,
+        args,
+        exe,
+        display_cmd,
         cwd,
-        project_root,
+        started_at,
+        cpu_percent: Some(process.cpu_usage()),
+        rss_bytes: process.memory(),
+        ppid,
+        pgid,
+        ancestors,
+        project_root: project.root,
+        package_root: project.package_root,
+        package_name: project.package_name,
+        git,
         container_name,
+        container,
+        port_forward,
+        tunnel,
+        service,
+        protected: None,
         kind,
         port,
+        protocol: listener.protocol,
+        bind_addr: listener.addr,
+        family: AddressFamily::of(&listener.addr),
     })
 }
 
-fn get_docker_containers() -> Result<HashMap<u16, String>> {
-    // docker ps --format "{{.Names}}\t{{.Ports}}"
-    // Output example:
-    // my-container	0.0.0.0:8080->80/tcp, :::8080->80/tcp
-
-    let output = Command::new("docker")
-        .arg("ps")
-        .arg("--format")
-        .arg("{{.Names}}\t{{.Ports}}")
-        .output();
-
-    match output {
-        Ok(output) => {
-            let stdout = String::from_utf8_lossy(&output.stdout);
-            let mut map = HashMap::new();
-
-            for line in stdout.lines() {
-                let parts: Vec<&str> = line.split('\t').collect();
-                if parts.len() >= 2 {
-                    let name = parts[0];
-                    let ports_str = parts[1];
-
-                    // Parse ports
-                    // 0.0.0.0:8080->80/tcp
-                    // 0.0.0.0:5432->5432/tcp, :::5432->5432/tcp
-
-                    for port_def in ports_str.split(',') {
-                        if let Some(host_part) = port_def.split("->").next() {
-                            // host_part: 0.0.0.0:8080 or :::8080
-                            if let Some(port_str) = host_part.split(':').next_back() {
-                                if let Ok(port) = port_str.parse::<u16>() {
-                                    map.insert(port, name.to_string());
-                                }
-                            }
-                        }
-                    }
-                }
-            }
-            Ok(map)
-        }
-        Err(_) => Ok(HashMap::new()), // Docker not installed or not running
-    }
-}
-
-fn get_all_listening_ports() -> Result<Vec<(u32, u16)>> {
-    if cfg!(target_os = "windows") {
-        scan_ports_windows()
-    } else {
-        scan_ports_unix()
-    }
-}
-
-fn scan_ports_unix() -> Result<Vec<(u32, u16)>> {
-    let output = Command::new("lsof")
-        .arg("-iTCP")
-        .arg("-sTCP:LISTEN")
-        .arg("-P")
-        .arg("-n")
-        .arg("-F")
-        .arg("pn") // Request PID ('p') and Name ('n') for network files
-        .output()
-        .context("Failed to execute lsof for scanning")?;
+const MAX_ANCESTORS: usize = 16;
 
-    let stdout = String::from_utf8_lossy(&output.stdout);
-    let mut results = Vec::new();
-    let mut current_pid = None;
+fn collect_ancestors(sys: &System, process: &sysinfo::Process) -> Vec<AncestorInfo> {
+    let mut ancestors: Vec<AncestorInfo> = Vec::new();
+    let mut current = process.parent();
 
-    for line in stdout.lines() {
-        if let Some(stripped) = line.strip_prefix('p') {
-            current_pid = stripped.parse::<u32>().ok();
-        } else if let Some(stripped) = line.strip_prefix('n') {
-            if let Some(pid) = current_pid {
-                // Example: n*:12345
-                let port_part = stripped.split(':').next_back().unwrap_or("");
-                if let Ok(port) = port_part.parse::<u16>() {
-                    results.push((pid, port));
-                }
-            }
-        }
-    }
-    Ok(results)
-}
-
-fn scan_ports_windows() -> Result<Vec<(u32, u16)>> {
-    let output = Command::new("netstat")
-        .arg("-ano")
-        .output()
-        .context("Failed to execute netstat")?;
-
-    let stdout = String::from_utf8_lossy(&output.stdout);
-    let mut results = Vec::new();
-
-    for line in stdout.lines() {
-        if line.contains("LISTENING") {
-            let parts: Vec<&str> = line.split_whitespace().collect();
-            // Expected format: Proto LocalAddress ForeignAddress State PID
-            // Example: TCP    0.0.0.0:8088           0.0.0.0:0              LISTENING       31715
-            if parts.len() >= 5 {
-                let local_addr = parts[1];
-                let pid_str = parts[parts.len() - 1];
-
-                if let Ok(port) = local_addr
-                    .split(':')
-                    .next_back()
-                    .unwrap_or("")
-                    .parse::<u16>()
-                {
-                    if let Ok(pid) = pid_str.parse::<u32>() {
-                        results.push((pid, port));
-                    }
-                }
-            }
-        }
-    }
-    Ok(results)
-}
-
-fn determine_kind(cmd: &str, cwd: &std::path::Path, _user: &str) -> ProcessKind {
-    let cwd_str = cwd.to_string_lossy();
-
-    if cmd.contains("docker") || cmd.contains("containerd") {
-        return ProcessKind::Docker;
-    }
-
-    if cwd_str.contains("/_workspace/") || cwd_str.contains("/_projects/") {
-        return ProcessKind::Dev;
-    }
-
-    if cwd_str.starts_with("/usr/sbin") || cwd_str.starts_with("/System") {
-        return ProcessKind::System;
-    }
-
-    if cwd_str.contains("/opt/homebrew") || cwd_str.contains("/usr/local/Cellar") {
-        return ProcessKind::Brew;
-    }
-
-    ProcessKind::Other
-}
-
-fn find_git_root(start_path: &std::path::Path) -> Option<PathBuf> {
-    if start_path.as_os_str().is_empty() {
-        return None;
-    }
-    let mut current = start_path;
-    loop {
-        if current.join(".git").exists() {
-            return Some(current.to_path_buf());
+    while let Some(ppid) = current {
+        // Stop at init and guard against pid reuse loops
+        if ppid.as_u32() <= 1
+            || ancestors.len() >= MAX_ANCESTORS
+            || ancestors.iter().any(|a| a.pid == ppid.as_u32())
+        {
+            break;
         }
-        match current.parent() {
-            Some(p) => current = p,
+        let parent = match sys.process(ppid) {
+            Some(parent) => parent,
             None => break,
-        }
+        };
+
+        #[cfg(unix)]
+        let uid = parent.user_id().map(|uid| **uid);
+        #[cfg(not(unix))]
+        let uid = None;
+
+        let cmd = parent.name().to_string();
+        let args = parent.cmd().to_vec();
+        ancestors.push(AncestorInfo {
+            pid: ppid.as_u32(),
+            uid,
+            display_cmd: cmdline::display_command(&cmd, &args),
+            cmd,
+            args,
+        });
+        current = parent.parent();
     }
-    None
+    ancestors
 }
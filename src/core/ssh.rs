@@ -0,0 +1,346 @@
+use serde::Serialize;
+use std::path::Path;
+
+// Port forwards set up on an ssh / autossh / sshuttle command line:
+//   ssh -fN -L 5432:db.internal:5432 -D 1080 deploy@bastion
+//   autossh -M 0 -N -L 8443:[fd00::10]:443 bastion
+//   sshuttle -r bastion 10.0.0.0/8
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+pub enum ForwardKind {
+    // -L: local port -> remote target
+    Local,
+    // -R: remote port -> local target
+    Remote,
+    // -D: local SOCKS proxy
+    Dynamic,
+}
+
+impl ForwardKind {
+    pub fn flag(&self) -> &'static str {
+        match self {
+            ForwardKind::Local => "-L",
+            ForwardKind::Remote => "-R",
+            ForwardKind::Dynamic => "-D",
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct Forward {
+    pub kind: ForwardKind,
+    pub bind_addr: Option<String>,
+    // None for unix socket forwards
+    pub listen_port: Option<u16>,
+    // host:port or a socket path; None for -D
+    pub target: Option<String>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct SshTunnel {
+    // ssh | autossh | sshuttle
+    pub tool: String,
+    // The ssh server the traffic goes through
+    pub via: Option<String>,
+    pub forwards: Vec<Forward>,
+    // sshuttle: networks routed through the tunnel
+    pub subnets: Vec<String>,
+}
+
+impl SshTunnel {
+    // "tunnel → db.internal:5432 via bastion" for the listener on `port`
+    pub fn label_for(&self, port: u16) -> String {
+        let forward = self
+            .forwards
+            .iter()
+            .find(|f| f.kind != ForwardKind::Remote && f.listen_port == Some(port));
+        let target = match forward {
+            Some(f) if f.kind == ForwardKind::Dynamic => Some("socks".to_string()),
+            Some(Forward {
+                target: Some(target),
+                ..
+            }) => Some(target.clone()),
+            // sshuttle's transparent proxy carries whole networks
+            _ => (!self.subnets.is_empty()).then(|| self.subnets.join(",")),
+        };
+
+        let mut label = match target {
+            Some(target) => format!("tunnel → {}", target),
+            None => "tunnel".to_string(),
+        };
+        if let Some(via) = &self.via {
+            label.push_str(&format!(" via {}", via));
+        }
+        label
+    }
+}
+
+// ssh options that take an argument (see ssh(1) SYNOPSIS)
+const SSH_VALUE_FLAGS: &str = "BbcDEeFIiJLlmOoPpQRSWw";
+
+pub fn parse_tunnel(cmd: &str, args: &[String]) -> Option<SshTunnel> {
+    let program = |arg: &String| {
+        Path::new(arg)
+            .file_name()
+            .map(|n| n.to_string_lossy().to_string())
+            .unwrap_or_default()
+    };
+    let first = args.first().map(program).unwrap_or_else(|| cmd.to_string());
+
+    match first.as_str() {
+        "ssh" => parse_ssh("ssh", args.get(1..).unwrap_or_default()),
+        "autossh" => {
+            // autossh's own -M <port> and -f come first; the rest is handed to ssh
+            let mut rest = args.get(1..).unwrap_or_default();
+            while let Some(arg) = rest.first() {
+                match arg.as_str() {
+                    "-M" => rest = rest.get(2..).unwrap_or_default(),
+                    "-f" => rest = &rest[1..],
+                    a if a.starts_with("-M") => rest = &rest[1..],
+                    _ => break,
+                }
+            }
+            parse_ssh("autossh", rest)
+        }
+        // Usually a python script: `python3 /usr/bin/sshuttle -r host ...`
+        _ => {
+            let pos = args.iter().take(2).position(|a| program(a) == "sshuttle")?;
+            parse_sshuttle(&args[pos + 1..])
+        }
+    }
+}
+
+fn parse_ssh(tool: &str, args: &[String]) -> Option<SshTunnel> {
+    let mut forwards = Vec::new();
+    let mut via = None;
+    let mut i = 0;
+    while i < args.len() {
+        let arg = &args[i];
+        i += 1;
+        if via.is_some() {
+            // Everything after the destination is the remote command
+            break;
+        }
+        let Some(bundle) = arg.strip_prefix('-').filter(|b| !b.is_empty()) else {
+            via = Some(strip_user(arg));
+            continue;
+        };
+
+        // Flags can be bundled: -fNL5432:db:5432 or -fNL 5432:db:5432
+        for (pos, flag) in bundle.char_indices() {
+            if !SSH_VALUE_FLAGS.contains(flag) {
+                continue;
+            }
+            let inline = &bundle[pos + flag.len_utf8()..];
+            let value = if inline.is_empty() {
+                i += 1;
+                match args.get(i - 1) {
+                    Some(value) => value.as_str(),
+                    None => break,
+                }
+            } else {
+                inline
+            };
+            let kind = match flag {
+                'L' => Some(ForwardKind::Local),
+                'R' => Some(ForwardKind::Remote),
+                'D' => Some(ForwardKind::Dynamic),
+                _ => None,
+            };
+            if let Some(forward) = kind.and_then(|k| parse_forward(k, value)) {
+                forwards.push(forward);
+            }
+            break;
+        }
+    }
+
+    if forwards.is_empty() {
+        return None;
+    }
+    Some(SshTunnel {
+        tool: tool.to_string(),
+        via,
+        forwards,
+        subnets: Vec::new(),
+    })
+}
+
+// [bind_address:]port:host:hostport, [bind_address:]port:socket, or [bind_address:]port for -D.
+// IPv6 addresses are written in brackets.
+pub fn parse_forward(kind: ForwardKind, spec: &str) -> Option<Forward> {
+    let parts = split_spec(spec);
+    let (bind_addr, listen, target) = match (kind, parts.as_slice()) {
+        (ForwardKind::Dynamic, [port]) => (None, port.as_str(), None),
+        (ForwardKind::Dynamic, [bind, port]) => (Some(bind.clone()), port.as_str(), None),
+        (_, [port, socket]) => (None, port.as_str(), Some(socket.clone())),
+        (_, [port, host, hostport]) => {
+            (None, port.as_str(), Some(format!("{}:{}", host, hostport)))
+        }
+        (_, [bind, port, host, hostport]) => (
+            Some(bind.clone()),
+            port.as_str(),
+            Some(format!("{}:{}", host, hostport)),
+        ),
+        _ => return None,
+    };
+    Some(Forward {
+        kind,
+        bind_addr,
+        listen_port: listen.parse().ok(),
+        target,
+    })
+}
+
+fn split_spec(spec: &str) -> Vec<String> {
+    let mut parts = Vec::new();
+    let mut current = String::new();
+    let mut in_brackets = false;
+    for c in spec.chars() {
+        match c {
+            '[' => in_brackets = true,
+            ']' => in_brackets = false,
+            ':' if !in_brackets => parts.push(std::mem::take(&mut current)),
+            _ => current.push(c),
+        }
+    }
+    parts.push(current);
+    parts
+}
+
+// sshuttle [-l [ip:]port] -r [user@]host[:port] subnet...
+fn parse_sshuttle(args: &[String]) -> Option<SshTunnel> {
+    const VALUE_FLAGS: &[&str] = &[
+        "-r",
+        "--remote",
+        "-l",
+        "--listen",
+        "-x",
+        "--exclude",
+        "-X",
+        "--exclude-from",
+        "-e",
+        "--ssh-cmd",
+        "--pidfile",
+        "--python",
+        "-s",
+        "--subnets",
+        "--ns-hosts",
+        "--to-ns",
+        "--method",
+        "--user",
+        "--group",
+        "--seed-hosts",
+    ];
+
+    let mut tunnel = SshTunnel {
+        tool: "sshuttle".to_string(),
+        via: None,
+        forwards: Vec::new(),
+        subnets: Vec::new(),
+    };
+    let mut i = 0;
+    while i < args.len() {
+        let arg = args[i].as_str();
+        i += 1;
+        if !arg.starts_with('-') {
+            tunnel.subnets.push(arg.to_string());
+            continue;
+        }
+
+        let (name, inline) = match arg.split_once('=') {
+            Some((name, value)) if name.starts_with("--") => (name, Some(value.to_string())),
+            // -rbastion
+            _ if arg.len() > 2 && !arg.starts_with("--") => (&arg[..2], Some(arg[2..].to_string())),
+            _ => (arg, None),
+        };
+        if !VALUE_FLAGS.contains(&name) {
+            continue;
+        }
+        let value = match inline {
+            Some(value) => value,
+            None => match args.get(i) {
+                Some(value) => {
+                    i += 1;
+                    value.clone()
+                }
+                None => break,
+            },
+        };
+        match name {
+            "-r" | "--remote" => {
+                // host:port -> host
+                let host = strip_user(&value);
+                let host = match host.rsplit_once(':') {
+                    Some((h, p)) if p.parse::<u16>().is_ok() => h.to_string(),
+                    _ => host,
+                };
+                tunnel.via = Some(host);
+            }
+            "-l" | "--listen" => {
+                let port = value.rsplit(':').next().and_then(|p| p.parse().ok());
+                tunnel.forwards.push(Forward {
+                    kind: ForwardKind::Local,
+                    bind_addr: None,
+                    listen_port: port,
+                    target: None,
+                });
+            }
+            _ => {}
+        }
+    }
+    Some(tunnel)
+}
+
+// ssh://deploy@bastion:2222 -> bastion:2222, deploy@bastion -> bastion
+fn strip_user(destination: &str) -> String {
+    let destination = destination.strip_prefix("ssh://").unwrap_or(destination);
+    destination
+        .rsplit_once('@')
+        .map(|(_, host)| host)
+        .unwrap_or(destination)
+        .to_string()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_tunnel() {
+        let argv = |s: &str| s.split_whitespace().map(String::from).collect::<Vec<_>>();
+
+        let args = argv("ssh -fNL 5432:db.internal:5432 -D 127.0.0.1:1080 -p 2222 deploy@bastion");
+        let tunnel = parse_tunnel("ssh", &args).unwrap();
+        assert_eq!(tunnel.tool, "ssh");
+        assert_eq!(tunnel.via.as_deref(), Some("bastion"));
+        assert_eq!(tunnel.forwards.len(), 2);
+        assert_eq!(tunnel.forwards[1].kind, ForwardKind::Dynamic);
+        assert_eq!(tunnel.forwards[1].bind_addr.as_deref(), Some("127.0.0.1"));
+        assert_eq!(
+            tunnel.label_for(5432),
+            "tunnel → db.internal:5432 via bastion"
+        );
+        assert_eq!(tunnel.label_for(1080), "tunnel → socks via bastion");
+
+        // autossh's monitor flag is skipped; brackets around IPv6 targets are dropped
+        let tunnel = parse_tunnel(
+            "autossh",
+            &argv("/usr/bin/autossh -M 0 -N -L8443:[fd00::10]:443 -R 9000:localhost:3000 jump"),
+        )
+        .unwrap();
+        assert_eq!(tunnel.label_for(8443), "tunnel → fd00::10:443 via jump");
+        assert_eq!(tunnel.forwards[1].kind, ForwardKind::Remote);
+
+        let tunnel = parse_tunnel(
+            "python3",
+            &argv("/usr/bin/python3 /usr/bin/sshuttle -l 12300 -r me@bastion:22 10.0.0.0/8"),
+        )
+        .unwrap();
+        assert_eq!(tunnel.tool, "sshuttle");
+        assert_eq!(tunnel.subnets, vec!["10.0.0.0/8"]);
+        assert_eq!(tunnel.label_for(12300), "tunnel → 10.0.0.0/8 via bastion");
+
+        // A plain interactive session forwards nothing
+        assert!(parse_tunnel("ssh", &argv("ssh -l root -i key.pem host uptime")).is_none());
+    }
+}
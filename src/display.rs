@@ -1,5 +1,9 @@
+use crate::audit::AuditReport;
+use crate::core::containers::ContainerInfo;
 use crate::core::ProcessInfo;
+use crate::ops::{KillOutcome, KillStep};
 use colored::*;
+use std::time::Duration;
 
 pub fn print_process_info(info: &ProcessInfo) {
     println!(
@@ -7,13 +11,194 @@ pub fn print_process_info(info: &ProcessInfo) {
         "port".green().bold(),
         info.port.to_string().green().bold()
     );
+    println!("  {:<9}: {}", "proto", info.protocol.as_str());
+    println!(
+        "  {:<9}: {} ({})",
+        "addr",
+        info.bind_addr,
+        info.family.as_str()
+    );
     println!("  {:<9}: {}", "pid", info.pid);
-    println!("  {:<9}: {}", "user", info.user);
+    match info.uid {
+        Some(uid) => println!("  {:<9}: {} (uid {})", "user", info.user, uid),
+        None => println!("  {:<9}: {}", "user", info.user),
+    }
     println!("  {:<9}: {}", "cmd", info.cmd);
+    if info.display_cmd != info.cmd {
+        println!("  {:<9}: {}", "command", info.display_cmd);
+    }
+    if let Some(exe) = &info.exe {
+        println!("  {:<9}: {}", "exe", exe.display());
+    }
+    if !info.args.is_empty() {
+        let args = info.args.join(" ");
+        println!(
+            "  {:<9}: {}",
+            "args",
+            args.split_whitespace().collect::<Vec<_>>().join(" ")
+        );
+    }
     println!("  {:<9}: {}", "cwd", info.cwd.display());
+    if !info.ancestors.is_empty() {
+        let chain: Vec<String> = info
+            .ancestors
+            .iter()
+            .take(3)
+            .map(|a| format!("{} ({})", a.display_cmd, a.pid))
+            .collect();
+        println!("  {:<9}: {}", "launched", chain.join(" <- "));
+    }
+    if let Some(uptime) = info.uptime() {
+        println!("  {:<9}: {}", "uptime", format_duration(uptime));
+    }
+    if let Some(cpu) = info.cpu_percent {
+        println!("  {:<9}: {:.1}%", "cpu", cpu);
+    }
+    println!("  {:<9}: {}", "mem", format_bytes(info.rss_bytes));
 
     if let Some(root) = &info.project_root {
-        println!("  {:<9}: {} (git)", "project", root.display());
+        println!("  {:<9}: {}", "project", root.display());
+    }
+    if let (Some(name), Some(root)) = (&info.package_name, &info.package_root) {
+        println!("  {:<9}: {} ({})", "package", name, root.display());
+    }
+    if let Some(git) = &info.git {
+        let head = git.head_label().unwrap_or_else(|| "?".to_string());
+        let state = match git.dirty {
+            Some(true) => " (dirty)",
+            Some(false) => " (clean)",
+            None => "",
+        };
+        println!("  {:<9}: {}{}", "git", head, state);
+        if git.is_linked_worktree() {
+            println!(
+                "  {:<9}: {} (of {})",
+                "worktree",
+                git.worktree.display(),
+                git.repo.display()
+            );
+        }
+    }
+
+    if let Some(c) = &info.container {
+        println!(
+            "  {:<9}: {} ({} {}, {})",
+            "container",
+            c.name,
+            c.runtime.as_str(),
+            c.short_id(),
+            c.image
+        );
+        let health = c
+            .health
+            .as_ref()
+            .map(|h| format!(" [{}]", h))
+            .unwrap_or_default();
+        println!("  {:<9}: {}{}", "status", c.status, health);
+        if let Some(project) = &c.compose_project {
+            let service = c.compose_service.as_deref().unwrap_or("?");
+            println!("  {:<9}: {} / {}", "compose", project, service);
+        }
+        let mut ports: Vec<String> = c
+            .ports
+            .iter()
+            .map(|p| {
+                format!(
+                    "{}->{}/{}",
+                    p.host_port,
+                    p.container_port,
+                    p.protocol.as_str()
+                )
+            })
+            .collect();
+        // IPv4 and IPv6 bindings of the same mapping are listed separately by the daemon
+        ports.dedup();
+        if !ports.is_empty() {
+            println!("  {:<9}: {}", "ports", ports.join(", "));
+        }
+    }
+
+    if let Some(forward) = &info.port_forward {
+        let mut scope = Vec::new();
+        if let Some(ns) = &forward.namespace {
+            scope.push(format!("namespace {}", ns));
+        }
+        if let Some(ctx) = &forward.context {
+            scope.push(format!("context {}", ctx));
+        }
+        let target = forward.target().unwrap_or_else(|| "?".to_string());
+        if scope.is_empty() {
+            println!("  {:<9}: {} ({})", "forward", target, forward.tool);
+        } else {
+            println!(
+                "  {:<9}: {} ({}, {})",
+                "forward",
+                target,
+                forward.tool,
+                scope.join(", ")
+            );
+        }
+        if !forward.ports.is_empty() {
+            let pairs: Vec<String> = forward
+                .ports
+                .iter()
+                .map(|p| {
+                    let local = p
+                        .local
+                        .map(|l| l.to_string())
+                        .unwrap_or_else(|| "*".to_string());
+                    format!("{}->{}", local, p.remote)
+                })
+                .collect();
+            println!("  {:<9}: {}", "ports", pairs.join(", "));
+        }
+        if !forward.addresses.is_empty() {
+            println!("  {:<9}: {}", "address", forward.addresses.join(", "));
+        }
+    }
+
+    if let Some(tunnel) = &info.tunnel {
+        let via = tunnel.via.as_deref().unwrap_or("?");
+        println!("  {:<9}: {} ({})", "tunnel", via, tunnel.tool);
+        let forwards: Vec<String> = tunnel
+            .forwards
+            .iter()
+            .map(|f| {
+                let listen = match (&f.bind_addr, f.listen_port) {
+                    (Some(addr), Some(port)) => format!("{}:{}", addr, port),
+                    (None, Some(port)) => port.to_string(),
+                    (_, None) => "?".to_string(),
+                };
+                match &f.target {
+                    Some(target) => format!("{} {}->{}", f.kind.flag(), listen, target),
+                    None => format!("{} {}", f.kind.flag(), listen),
+                }
+            })
+            .collect();
+        if !forwards.is_empty() {
+            println!("  {:<9}: {}", "forwards", forwards.join(", "));
+        }
+        if !tunnel.subnets.is_empty() {
+            println!("  {:<9}: {}", "subnets", tunnel.subnets.join(", "));
+        }
+    }
+
+    if let Some(unit) = &info.service {
+        println!(
+            "  {:<9}: {} ({} {})",
+            "service",
+            unit.name,
+            unit.manager_name(),
+            unit.scope_name()
+        );
+    }
+
+    if let Some(reason) = &info.protected {
+        println!(
+            "  {:<9}: {} ([kill] protect)",
+            "protected",
+            reason.yellow().bold()
+        );
     }
 
     let kind_str = format!("[{}]", info.kind.as_str());
@@ -23,42 +208,278 @@ pub fn print_process_info(info: &ProcessInfo) {
     println!();
 }
 
-pub fn print_scan_result(infos: &[ProcessInfo]) {
+pub fn print_scan_result(infos: &[ProcessInfo], wide: bool) {
     if infos.is_empty() {
         println!("No listening ports found in range.");
         return;
     }
 
-    println!(
-        "{:<6} {:<8} {:<8} {:<8} {:<8} PROJ",
-        "PORT", "PID", "USER", "CMD", "KIND"
+    print!(
+        "{:<6} {:<5} {:<8} {:<8} {:<8} {:<8} ",
+        "PORT", "PROTO", "PID", "USER", "CMD", "KIND"
     );
+    if wide {
+        print!("{:<24} ", "COMMAND");
+    }
+    println!("PROJ");
+    let mut current_stack = None;
     for info in infos {
-        let proj = if let Some(container) = &info.container_name {
-            container.clone()
-        } else {
-            info.project_root
-                .as_ref()
-                .and_then(|p| p.file_name())
-                .map(|n| n.to_string_lossy().to_string())
-                .unwrap_or_default()
-        };
+        // Callers group stack members together (ops::group_by_stack); label each group once
+        if info.stack().is_some() && info.stack() != current_stack {
+            println!(
+                "{}",
+                format!("stack {}", info.stack().unwrap_or_default()).bold()
+            );
+        }
+        current_stack = info.stack();
 
-        println!(
-            "{:<6} {:<8} {:<8} {:<8} {:<8} {}",
+        let proj = project_column(info);
+
+        print!(
+            "{:<6} {:<5} {:<8} {:<8} {:<8} {:<8} ",
             info.port,
+            info.protocol.as_str(),
             info.pid,
             truncate(&info.user, 8),
             truncate(&info.cmd, 8),
             info.kind.as_str(),
-            proj
         );
+        if wide {
+            print!("{:<24} ", truncate(&info.display_cmd, 24));
+        }
+        println!("{}", proj);
+    }
+}
+
+pub fn print_stack_ports(project: &str, containers: &[ContainerInfo]) {
+    println!("{} {}", "stack".green().bold(), project.green().bold());
+    println!(
+        "{:<16} {:<24} {:<16} {:<10} {:<5} STATUS",
+        "SERVICE", "CONTAINER", "HOST", "CONTAINER", "PROTO"
+    );
+    for c in containers {
+        let mut ports: Vec<String> = c
+            .ports
+            .iter()
+            .map(|p| {
+                let host = match p.host_ip {
+                    Some(ip) if !ip.is_unspecified() => format!("{}:{}", ip, p.host_port),
+                    _ => p.host_port.to_string(),
+                };
+                format!(
+                    "{:<16} {:<10} {:<5}",
+                    host,
+                    p.container_port,
+                    p.protocol.as_str()
+                )
+            })
+            .collect();
+        ports.dedup();
+        if ports.is_empty() {
+            ports.push(format!("{:<16} {:<10} {:<5}", "-", "-", "-"));
+        }
+        for port in ports {
+            println!(
+                "{:<16} {:<24} {} {}",
+                truncate(c.compose_service.as_deref().unwrap_or("?"), 16),
+                truncate(&c.name, 24),
+                port,
+                c.status
+            );
+        }
+    }
+}
+
+pub fn print_audit_report(report: &AuditReport, violations_only: bool) {
+    let findings: Vec<_> = report
+        .findings
+        .iter()
+        .filter(|f| !violations_only || f.violation)
+        .collect();
+
+    if !findings.is_empty() {
+        println!(
+            "{:<9} {:<6} {:<5} {:<16} {:<8} {:<8} {:<8}",
+            "EXPOSURE", "PORT", "PROTO", "ADDR", "PID", "CMD", "KIND"
+        );
+    }
+    for finding in &findings {
+        let info = &finding.process;
+        let exposure = format!("{:<9}", finding.exposure.as_str());
+        let line = format!(
+            "{} {:<6} {:<5} {:<16} {:<8} {:<8} {:<8}",
+            exposure,
+            info.port,
+            info.protocol.as_str(),
+            truncate(&info.bind_addr.to_string(), 16),
+            info.pid,
+            truncate(&info.cmd, 8),
+            info.kind.as_str()
+        );
+        if finding.violation {
+            println!("{} {}", line.red(), "VIOLATION".red().bold());
+        } else {
+            println!("{}", line);
+        }
+    }
+
+    let violations = report.violations();
+    let policy = format!(
+        "{} must stay within {}",
+        report.kinds.join(", "),
+        report.max_exposure.as_str()
+    );
+    if violations == 0 {
+        println!("{} ({})", "No exposure policy violations".green(), policy);
+    } else {
+        println!(
+            "{} ({})",
+            format!("{} exposure policy violation(s)", violations)
+                .red()
+                .bold(),
+            policy
+        );
+    }
+}
+
+// PROJ column: the container for containerized listeners, otherwise repo[/package]@branch
+// with a trailing `*` for uncommitted changes
+pub fn project_column(info: &ProcessInfo) -> String {
+    // Compose services read better as stack/service than as generated container names
+    if let Some(c) = &info.container {
+        if let (Some(project), Some(service)) = (&c.compose_project, &c.compose_service) {
+            return format!("{}/{}", project, service);
+        }
+    }
+    if let Some(tunnel) = &info.tunnel {
+        return tunnel.label_for(info.port);
+    }
+    if let Some(container) = &info.container_name {
+        return container.clone();
+    }
+    let mut label = info.project_label().unwrap_or_default();
+    if let Some(git) = &info.git {
+        if let Some(head) = git.head_label() {
+            label = format!("{}@{}", label, head);
+        }
+        if git.dirty == Some(true) {
+            label.push('*');
+        }
+    }
+    label
+}
+
+pub fn kill_outcome_message(subject: &str, outcome: &KillOutcome) -> String {
+    let pids = |holders: &[u32]| {
+        holders
+            .iter()
+            .map(|p| p.to_string())
+            .collect::<Vec<_>>()
+            .join(", ")
+    };
+    let last = |steps: &[KillStep]| steps.last().map(|s| s.signal.clone()).unwrap_or_default();
+    match outcome {
+        KillOutcome::DryRun { action } => format!("Would {}", action),
+        KillOutcome::Signalled { signal } => format!("Sent {} to {}", signal, subject),
+        KillOutcome::Stopped {
+            steps,
+            freed_port: Some(port),
+        } => format!(
+            "{} stopped after {}; port {} is free",
+            subject,
+            last(steps),
+            port
+        ),
+        KillOutcome::Stopped { steps, .. } => {
+            format!("{} exited after {}", subject, last(steps))
+        }
+        KillOutcome::AlreadyExited => format!("{} not found or already exited", subject),
+        KillOutcome::StillRunning {
+            steps,
+            port: Some(port),
+            holders,
+        } if !holders.is_empty() => format!(
+            "Port {} is still in use by PID {} after {} to {}",
+            port,
+            pids(holders),
+            last(steps),
+            subject
+        ),
+        KillOutcome::StillRunning { steps, .. } => {
+            format!("{} is still running after {}", subject, last(steps))
+        }
+        KillOutcome::Respawned { port, holders } => format!(
+            "{} already exited, but port {} is still in use by PID {} (respawned by a supervisor? try --parent)",
+            subject,
+            port,
+            pids(holders)
+        ),
+        #[cfg(windows)]
+        KillOutcome::Terminated => format!("Killed {}", subject),
+        KillOutcome::Container { action } => format!("{} {}", action.past_tense(), subject),
+        KillOutcome::Service { command } => format!("Stopped {} ({})", subject, command),
+        KillOutcome::Skipped { reason } => format!("Skipped {} ({})", subject, reason),
+        KillOutcome::Refused { reason } => reason.clone(),
+    }
+}
+
+pub fn print_kill_outcome(subject: &str, outcome: &KillOutcome) {
+    let message = kill_outcome_message(subject, outcome);
+    match outcome {
+        KillOutcome::Refused { .. } => eprintln!("{} {}", "Refused:".red().bold(), message),
+        _ if outcome.failed() => eprintln!("{}", message.red()),
+        _ => println!("{}", message),
+    }
+}
+
+// Laid out like `kill -l`
+pub fn print_signals(signals: &[(i32, &str)]) {
+    if signals.is_empty() {
+        println!("Signals are not supported on this platform.");
+        return;
+    }
+    for row in signals.chunks(6) {
+        let cells: Vec<String> = row
+            .iter()
+            .map(|(number, name)| format!("{:>2}) {:<9}", number, name.trim_start_matches("SIG")))
+            .collect();
+        println!("{}", cells.join(" ").trim_end());
+    }
+}
+
+pub fn format_duration(d: Duration) -> String {
+    let secs = d.as_secs();
+    let (days, hours, mins) = (secs / 86400, (secs % 86400) / 3600, (secs % 3600) / 60);
+    if days > 0 {
+        format!("{}d {}h", days, hours)
+    } else if hours > 0 {
+        format!("{}h {}m", hours, mins)
+    } else if mins > 0 {
+        format!("{}m {}s", mins, secs % 60)
+    } else {
+        format!("{}s", secs)
+    }
+}
+
+pub fn format_bytes(bytes: u64) -> String {
+    const UNITS: [&str; 4] = ["B", "KiB", "MiB", "GiB"];
+    let mut value = bytes as f64;
+    let mut unit = 0;
+    while value >= 1024.0 && unit < UNITS.len() - 1 {
+        value /= 1024.0;
+        unit += 1;
+    }
+    if unit == 0 {
+        format!("{} {}", bytes, UNITS[0])
+    } else {
+        format!("{:.1} {}", value, UNITS[unit])
     }
 }
 
 fn truncate(s: &str, max_width: usize) -> String {
-    if s.len() > max_width {
-        format!("{}...", &s[0..max_width - 3])
+    if s.chars().count() > max_width {
+        let head: String = s.chars().take(max_width - 3).collect();
+        format!("{}...", head)
     } else {
         s.to_string()
     }
@@ -1,3 +1,4 @@
+mod audit;
 mod cli;
 mod config;
 mod core;
@@ -9,24 +10,44 @@ mod utils;
 use anyhow::Result;
 use clap::Parser;
 use cli::{Cli, Commands};
-use core::SystemSnapshot;
+use core::containers::ContainerAction;
+use core::launch::LaunchSpec;
 use std::io::{self, Write};
 
 fn main() -> Result<()> {
     let cli = Cli::parse();
-    let config = config::load_config(cli.config.as_ref()).unwrap_or_default();
+    let mut config = config::load_config(cli.config.as_ref())?;
+    if let Some(backend) = cli.backend {
+        config.backend.strategy = backend;
+    }
 
     // Capture system state once
     // For UI, we might capture inside UI loop, but here we capture for CLI commands.
     // If UI command, we can ignore this snapshot or pass it.
     // TUI App::new() calls capture() again. That's fine.
-    let snapshot = SystemSnapshot::capture()?;
+    // CPU usage takes a second sample ~200 ms later; only the port view and `scan --sort cpu`
+    // show it
+    let measure_cpu = match &cli.command {
+        Some(Commands::Scan { sort, .. }) => *sort == ops::SortKey::Cpu,
+        Some(_) => false,
+        None => true,
+    };
+    let sys = core::SystemSnapshot::new_system(measure_cpu);
+    let snapshot = ops::capture_snapshot(&config, &sys, measure_cpu)?;
 
     match &cli.command {
         Some(Commands::Ui) => {
-            tui::run_tui()?;
+            tui::run_tui(config.clone())?;
         }
-        Some(Commands::Scan { from, to, json }) => {
+        Some(Commands::Scan {
+            from,
+            to,
+            proto,
+            addr,
+            wide,
+            sort,
+            json,
+        }) => {
             let (cfg_from, cfg_to) = if let Some(range) = &config.scan.default_range {
                 let parts: Vec<&str> = range.split('-').collect();
                 if parts.len() == 2 {
@@ -41,13 +62,90 @@ fn main() -> Result<()> {
             let final_from = from.or(cfg_from).unwrap_or(3000);
             let final_to = to.or(cfg_to).unwrap_or(9999);
 
-            let results = ops::scan_ports(&snapshot, final_from, final_to)?;
+            let filter = ops::ScanFilter {
+                from: final_from,
+                to: final_to,
+                proto: *proto,
+                addr: *addr,
+            };
+            let mut results = ops::scan_ports(&snapshot, &filter)?;
+            ops::sort_infos(&mut results, *sort);
+            ops::group_by_stack(&mut results);
 
             if *json {
                 let json_output = serde_json::to_string_pretty(&results)?;
                 println!("{}", json_output);
             } else {
-                display::print_scan_result(&results);
+                display::print_scan_result(&results, *wide);
+            }
+        }
+        Some(Commands::Stack {
+            project,
+            action,
+            dry_run,
+            interactive,
+            override_protection,
+        }) => {
+            let containers = ops::stack_containers(project)?;
+            let container_action = match action {
+                ops::StackAction::Ports => {
+                    display::print_stack_ports(project, &containers);
+                    return Ok(());
+                }
+                ops::StackAction::Down => ContainerAction::Rm,
+                ops::StackAction::Restart => ContainerAction::Restart,
+            };
+            // Refuse the whole stack rather than leave it half down
+            for c in &containers {
+                ops::check_container_protection(&config.kill.protect, c, *override_protection)?;
+            }
+
+            let final_interactive = interactive.or(config.kill.confirm).unwrap_or(true);
+            if final_interactive {
+                display::print_stack_ports(project, &containers);
+                let question = format!(
+                    "{} {} container(s) of stack {}?",
+                    container_action.verb(),
+                    containers.len(),
+                    project
+                );
+                if !confirm(&question)? {
+                    println!("Skipped.");
+                    return Ok(());
+                }
+            }
+            for c in &containers {
+                let outcome = ops::stop_container(c, container_action, *dry_run)?;
+                display::print_kill_outcome(&ops::container_subject(c), &outcome);
+            }
+        }
+        Some(Commands::Audit {
+            from,
+            to,
+            violations_only,
+            json,
+        }) => {
+            let filter = ops::ScanFilter {
+                from: *from,
+                to: *to,
+                proto: ops::ProtocolFilter::All,
+                addr: None,
+            };
+            let infos = ops::scan_ports(&snapshot, &filter)?;
+            let mut report = audit::audit(&infos, &config.audit);
+
+            if *json {
+                if *violations_only {
+                    report.findings.retain(|f| f.violation);
+                }
+                println!("{}", serde_json::to_string_pretty(&report)?);
+            } else {
+                display::print_audit_report(&report, *violations_only);
+            }
+
+            // The report already explains the failure; just signal it to hooks / CI
+            if report.violations() > 0 {
+                std::process::exit(1);
             }
         }
         Some(Commands::Suggest {
@@ -67,51 +165,335 @@ fn main() -> Result<()> {
             dry_run,
             interactive,
             all_users,
+            override_protection,
             signal,
+            list_signals,
             force,
+            tree,
+            parent,
+            container,
+            json,
+            range,
+            kind,
+            project,
+            cmd,
+            user,
         }) => {
-            let infos = snapshot.get_process_info(*port);
-            if infos.is_empty() {
-                println!("No process found on port {}", port);
+            if *list_signals {
+                display::print_signals(&ops::signal_list());
                 return Ok(());
             }
 
-            #[cfg(unix)]
-            let _current_uid = nix::unistd::getuid();
-            #[cfg(not(unix))]
-            let _current_uid = 0; // Dummy value for Windows
+            let kill_filter = ops::KillFilter {
+                kind: kind.clone(),
+                project: project.clone(),
+                cmd: cmd.clone(),
+                user: user.clone(),
+            };
+            // Anything but a single port is a bulk kill: preview, then one confirmation
+            let bulk = range.is_some() || !kill_filter.is_empty();
+            let (from, to) = match (port, range) {
+                (Some(port), _) => (*port, *port),
+                (None, Some(range)) => *range,
+                (None, None) => (1, u16::MAX),
+            };
+            let filter = ops::ScanFilter {
+                from,
+                to,
+                proto: ops::ProtocolFilter::All,
+                addr: None,
+            };
+            let infos: Vec<_> = ops::scan_ports(&snapshot, &filter)?
+                .into_iter()
+                .filter(|i| kill_filter.matches(i))
+                .collect();
+            if infos.is_empty() {
+                if *json {
+                    println!("[]");
+                } else if bulk {
+                    println!("No listeners match the given filters");
+                } else {
+                    println!("No process found on port {}", from);
+                }
+                return Ok(());
+            }
 
             let final_interactive = interactive.or(config.kill.confirm).unwrap_or(true);
-            let final_signal = signal.as_deref().or(config.kill.default_signal.as_deref());
+            // Reject a mistyped --signal before anything is stopped
+            if let Some(name) = signal {
+                ops::parse_signal(name)?;
+            }
 
-            for info in infos {
-                display::print_process_info(&info);
+            if bulk {
+                if !*json {
+                    display::print_scan_result(&infos, false);
+                    println!();
+                }
+                if final_interactive
+                    && !confirm(&format!("Kill {} listener(s) listed above?", infos.len()))?
+                {
+                    println!("Skipped.");
+                    return Ok(());
+                }
+            }
+            // One question for the whole selection instead of one per process
+            let confirm_each = final_interactive && !bulk;
 
-                // Check permission (simplified)
-                // In a real app we'd check info.uid vs current_uid
-                // But ProcessInfo currently stores user as String.
-                // We should probably store uid in ProcessInfo or just trust the user string?
-                // For v0, let's just warn if not --all-users and it looks like system.
+            let scope = if *tree {
+                ops::TargetScope::Tree
+            } else if *parent {
+                ops::TargetScope::Parent
+            } else {
+                ops::TargetScope::Listener
+            };
 
-                if !all_users && info.kind == crate::core::ProcessKind::System {
-                    println!("Skipping system process (use --all-users to override)");
-                    continue;
+            let backend = config.backend.strategy.backend();
+            let mut records = Vec::new();
+            // Several listeners can resolve to the same parent / group; signal it once
+            let mut handled = std::collections::HashSet::new();
+            let mut handled_containers = std::collections::HashSet::new();
+            let mut handled_services = std::collections::HashSet::new();
+            for info in infos {
+                if !*json && !bulk {
+                    display::print_process_info(&info);
                 }
 
-                if final_interactive {
-                    print!("Kill process {}? [y/N] ", info.pid);
-                    io::stdout().flush()?;
-                    let mut input = String::new();
-                    io::stdin().read_line(&mut input)?;
-                    if input.trim().to_lowercase() != "y" {
-                        println!("Skipped.");
-                        continue;
-                    }
+                let route = ops::kill_route(
+                    &info,
+                    signal.as_deref(),
+                    &config.kill,
+                    *force,
+                    container.is_some(),
+                );
+                // How the listener was started; only recorded for processes that are really killed
+                let mut launch = None;
+                let (subject, outcome) =
+                    if let Err(e) = ops::check_protection(&info, *override_protection) {
+                        // A bulk kill just leaves protected listeners out; naming one is an error
+                        let outcome = match &info.protected {
+                            Some(reason) if bulk => ops::KillOutcome::Skipped {
+                                reason: format!("protected: {}", reason),
+                            },
+                            _ => ops::KillOutcome::Refused {
+                                reason: e.to_string(),
+                            },
+                        };
+                        (format!("PID {}", info.pid), outcome)
+                    } else if let Some(c) = info
+                        .container
+                        .as_ref()
+                        .filter(|_| route == ops::KillRoute::Container)
+                    {
+                        // Published by a container: the listener is only a proxy, act on the container
+                        if !handled_containers.insert(c.id.clone()) {
+                            continue;
+                        }
+                        let action = container.unwrap_or(if *force {
+                            ContainerAction::Kill
+                        } else {
+                            ContainerAction::Stop
+                        });
+                        let subject = ops::container_subject(c);
+                        if confirm_each && !confirm(&format!("{} {}?", action.verb(), subject))? {
+                            (subject, declined())
+                        } else {
+                            (subject, ops::stop_container(c, action, *dry_run)?)
+                        }
+                    } else if !all_users && info.kind == crate::core::ProcessKind::System {
+                        (
+                            format!("PID {}", info.pid),
+                            ops::KillOutcome::Skipped {
+                                reason: "system process; use --all-users to override".to_string(),
+                            },
+                        )
+                    } else if let Some(unit) = info
+                        .service
+                        .as_ref()
+                        .filter(|_| route == ops::KillRoute::Service)
+                    {
+                        // Supervised by systemd / launchd: a killed process would just be restarted
+                        if !handled_services.insert(unit.name.clone()) {
+                            continue;
+                        }
+                        let subject = ops::service_subject(unit);
+                        if let Err(e) = ops::check_ownership(&info, *all_users) {
+                            (
+                                subject,
+                                ops::KillOutcome::Refused {
+                                    reason: e.to_string(),
+                                },
+                            )
+                        } else if confirm_each
+                            && !confirm(&format!(
+                                "Stop {} ({})?",
+                                subject,
+                                unit.stop_command().join(" ")
+                            ))?
+                        {
+                            (subject, declined())
+                        } else {
+                            (subject, ops::stop_service(unit, *dry_run)?)
+                        }
+                    } else {
+                        match ops::resolve_kill_target(&info, scope, *all_users) {
+                            Err(e) => (
+                                format!("PID {}", info.pid),
+                                ops::KillOutcome::Refused {
+                                    reason: e.to_string(),
+                                },
+                            ),
+                            Ok(target) => {
+                                if !handled.insert(target) {
+                                    continue;
+                                }
+                                if confirm_each && !confirm(&format!("Kill {}?", target))? {
+                                    (target.to_string(), declined())
+                                } else {
+                                    let watch = ops::PortWatch {
+                                        backend: backend.as_ref(),
+                                        port: info.port,
+                                        protocol: info.protocol,
+                                        addr: info.bind_addr,
+                                    };
+                                    // Read before the process is gone. With --parent / --tree
+                                    // the supervisor or group goes, not the listener described here
+                                    if !*dry_run && scope == ops::TargetScope::Listener {
+                                        launch = Some(LaunchSpec::capture(&info));
+                                    }
+                                    let final_signal = match route {
+                                        ops::KillRoute::Process(signal) => signal,
+                                        _ => None,
+                                    };
+                                    let outcome = ops::kill_process(
+                                        target,
+                                        final_signal,
+                                        *force,
+                                        *dry_run,
+                                        &config.kill.ladder,
+                                        Some(&watch),
+                                    )?;
+                                    (target.to_string(), outcome)
+                                }
+                            }
+                        }
+                    };
+
+                if !*json {
+                    display::print_kill_outcome(&subject, &outcome);
                 }
+                records.push(ops::KillRecord {
+                    port: info.port,
+                    protocol: info.protocol,
+                    pid: info.pid,
+                    cmd: info.cmd.clone(),
+                    target: subject,
+                    outcome,
+                    launch,
+                });
+            }
 
-                ops::kill_process(info.pid, final_signal, *force, *dry_run)?;
+            if *json {
+                println!("{}", serde_json::to_string_pretty(&records)?);
+            }
+            let failed = records.iter().filter(|r| r.outcome.failed()).count();
+            if failed > 0 {
+                anyhow::bail!(
+                    "{} of {} target(s) could not be stopped; their ports are still in use",
+                    failed,
+                    records.len()
+                );
             }
         }
+        Some(Commands::Restart {
+            port,
+            new_port,
+            dry_run,
+            interactive,
+            all_users,
+            override_protection,
+        }) => {
+            let infos = snapshot.get_process_info(*port);
+            let Some(info) = infos.first() else {
+                anyhow::bail!("No process found on port {}", port);
+            };
+            if infos.iter().any(|i| i.pid != info.pid) {
+                anyhow::bail!(
+                    "Port {} is held by several processes; use `crossport kill {}` instead",
+                    port,
+                    port
+                );
+            }
+            if let Some(c) = &info.container {
+                anyhow::bail!(
+                    "Port {} is published by {}; use `crossport kill {} --container restart`",
+                    port,
+                    ops::container_subject(c),
+                    port
+                );
+            }
+            if let Some(unit) = &info.service {
+                anyhow::bail!(
+                    "Port {} belongs to {}; restart it through its service manager",
+                    port,
+                    ops::service_subject(unit)
+                );
+            }
+            ops::check_protection(info, *override_protection)?;
+            let target = ops::resolve_kill_target(info, ops::TargetScope::Listener, *all_users)?;
+            // Read before the process is gone
+            let launch = LaunchSpec::capture(info);
+            // Nothing is killed unless the command can be started again
+            launch.program()?;
+            display::print_process_info(info);
+
+            let command = launch.argv.join(" ");
+            let final_interactive = interactive.or(config.kill.confirm).unwrap_or(true);
+            if final_interactive && !confirm(&format!("Restart {} ({})?", target, command))? {
+                println!("Skipped.");
+                return Ok(());
+            }
+
+            let backend = config.backend.strategy.backend();
+            let watch = ops::PortWatch {
+                backend: backend.as_ref(),
+                port: *port,
+                protocol: info.protocol,
+                addr: info.bind_addr,
+            };
+            let outcome = ops::kill_process(
+                target,
+                None,
+                false,
+                *dry_run,
+                &config.kill.ladder,
+                Some(&watch),
+            )?;
+            display::print_kill_outcome(&target.to_string(), &outcome);
+            if outcome.failed() {
+                anyhow::bail!("{} did not stop; not starting it again", target);
+            }
+
+            let port_note = new_port
+                .map(|p| format!(" with PORT={}", p))
+                .unwrap_or_default();
+            if *dry_run {
+                println!(
+                    "Would start `{}` in {}{}",
+                    command,
+                    launch.cwd.display(),
+                    port_note
+                );
+                return Ok(());
+            }
+            let pid = launch.spawn(*new_port)?;
+            println!(
+                "Started `{}` as PID {} in {}{}",
+                command,
+                pid,
+                launch.cwd.display(),
+                port_note
+            );
+        }
         None => {
             if cli.ports.is_empty() {
                 // Default behavior if no args? Maybe help?
@@ -138,3 +520,18 @@ fn main() -> Result<()> {
 
     Ok(())
 }
+
+fn declined() -> ops::KillOutcome {
+    ops::KillOutcome::Skipped {
+        reason: "declined".to_string(),
+    }
+}
+
+// Prompts go to stderr so that they never end up in --json output
+fn confirm(question: &str) -> Result<bool> {
+    eprint!("{} [y/N] ", question);
+    io::stderr().flush()?;
+    let mut input = String::new();
+    io::stdin().read_line(&mut input)?;
+    Ok(input.trim().to_lowercase() == "y")
+}
@@ -1,5 +1,19 @@
-use crate::core::{ProcessInfo, SystemSnapshot};
+use crate::config::Config;
+use crate::config::{EscalationStep, KillConfig, ProtectConfig};
+use crate::core::backend::PortBackend;
+use crate::core::classify::Classifier;
+use crate::core::containers::{self, ContainerAction, ContainerInfo};
+use crate::core::launch::LaunchSpec;
+use crate::core::service::ServiceUnit;
+use crate::core::{ProcessInfo, ProcessKind, Protocol, SystemSnapshot};
 use anyhow::{Context, Result};
+use serde::Serialize;
+use std::net::IpAddr;
+use std::str::FromStr;
+use std::time::Duration;
+#[cfg(unix)]
+use std::time::Instant;
+use sysinfo::System;
 
 #[cfg(unix)]
 use nix::sys::signal::{self, Signal};
@@ -8,7 +22,228 @@ use nix::unistd::Pid;
 
 #[cfg(windows)]
 use std::process::Command;
-pub fn scan_ports(snapshot: &SystemSnapshot, from: u16, to: u16) -> Result<Vec<ProcessInfo>> {
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, clap::ValueEnum)]
+pub enum ProtocolFilter {
+    Tcp,
+    Udp,
+    #[default]
+    All,
+}
+
+impl ProtocolFilter {
+    pub fn matches(&self, protocol: Protocol) -> bool {
+        match self {
+            ProtocolFilter::Tcp => protocol == Protocol::Tcp,
+            ProtocolFilter::Udp => protocol == Protocol::Udp,
+            ProtocolFilter::All => true,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, clap::ValueEnum)]
+pub enum SortKey {
+    #[default]
+    Port,
+    Uptime,
+    Mem,
+    Cpu,
+}
+
+// Port sorts ascending; the resource keys put the longest-running / heaviest processes first.
+pub fn sort_infos(infos: &mut [ProcessInfo], key: SortKey) {
+    match key {
+        SortKey::Port => infos.sort_by_key(|i| (i.port, i.protocol, i.bind_addr)),
+        SortKey::Uptime => infos.sort_by_key(|i| std::cmp::Reverse(i.uptime())),
+        SortKey::Mem => infos.sort_by_key(|i| std::cmp::Reverse(i.rss_bytes)),
+        SortKey::Cpu => infos.sort_by(|a, b| {
+            let cpu = |i: &ProcessInfo| i.cpu_percent.unwrap_or_default();
+            cpu(b).total_cmp(&cpu(a))
+        }),
+    }
+}
+
+// Keeps the listeners of one compose project together (after the standalone ones) without
+// disturbing the order inside each group
+pub fn group_by_stack(infos: &mut [ProcessInfo]) {
+    infos.sort_by(|a, b| a.stack().cmp(&b.stack()));
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum AddrFilter {
+    Loopback,
+    Any,
+    Ip(IpAddr),
+}
+
+impl AddrFilter {
+    pub fn matches(&self, addr: &IpAddr) -> bool {
+        match self {
+            AddrFilter::Loopback => addr.is_loopback(),
+            AddrFilter::Any => addr.is_unspecified(),
+            AddrFilter::Ip(ip) => addr == ip,
+        }
+    }
+}
+
+impl FromStr for AddrFilter {
+    type Err = String;
+
+    fn from_str(s: &str) -> std::result::Result<Self, Self::Err> {
+        match s.to_lowercase().as_str() {
+            "loopback" => Ok(AddrFilter::Loopback),
+            "any" => Ok(AddrFilter::Any),
+            other => other
+                .trim_start_matches('[')
+                .trim_end_matches(']')
+                .parse::<IpAddr>()
+                .map(|ip| AddrFilter::Ip(ip.to_canonical()))
+                .map_err(|_| format!("expected loopback, any or an IP address, got '{}'", s)),
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct ScanFilter {
+    pub from: u16,
+    pub to: u16,
+    pub proto: ProtocolFilter,
+    pub addr: Option<AddrFilter>,
+}
+
+impl ScanFilter {
+    pub fn matches(&self, info: &ProcessInfo) -> bool {
+        info.port >= self.from
+            && info.port <= self.to
+            && self.proto.matches(info.protocol)
+            && self.addr.is_none_or(|a| a.matches(&info.bind_addr))
+    }
+}
+
+// The selection filters of `crossport kill`, applied on top of a ScanFilter
+#[derive(Debug, Clone, Default)]
+pub struct KillFilter {
+    pub kind: Option<String>,
+    pub project: Option<String>,
+    pub cmd: Option<String>,
+    pub user: Option<String>,
+}
+
+impl KillFilter {
+    pub fn is_empty(&self) -> bool {
+        self.kind.is_none() && self.project.is_none() && self.cmd.is_none() && self.user.is_none()
+    }
+
+    pub fn matches(&self, info: &ProcessInfo) -> bool {
+        self.kind
+            .as_ref()
+            .is_none_or(|k| ProcessKind::from_name(k) == info.kind)
+            && self
+                .project
+                .as_ref()
+                .is_none_or(|p| matches_project(info, p))
+            && self.cmd.as_ref().is_none_or(|c| matches_cmd(info, c))
+            && self.user.as_ref().is_none_or(|u| info.user == *u)
+    }
+}
+
+// "myapp" matches the repo, its packages ("myapp/web") and a compose stack of that name
+fn matches_project(info: &ProcessInfo, project: &str) -> bool {
+    let label = info.project_label();
+    label.as_deref() == Some(project)
+        || label
+            .as_deref()
+            .and_then(|l| l.split_once('/'))
+            .is_some_and(|(root, _)| root == project)
+        || info.package_name.as_deref() == Some(project)
+        || info
+            .project_root
+            .as_ref()
+            .and_then(|r| r.file_name())
+            .is_some_and(|n| n == project)
+        || info.stack() == Some(project)
+}
+
+// The process name or the file name of its executable ("node", "postgres")
+fn matches_cmd(info: &ProcessInfo, cmd: &str) -> bool {
+    info.cmd == cmd
+        || info
+            .exe
+            .as_ref()
+            .and_then(|e| e.file_name())
+            .is_some_and(|n| n == cmd)
+}
+
+// The first `[kill] protect` entry covering the listener, e.g. "port 5432" or "user postgres"
+pub fn protection_reason(protect: &ProtectConfig, info: &ProcessInfo) -> Option<String> {
+    if protect.ports.contains(&info.port) {
+        return Some(format!("port {}", info.port));
+    }
+    if let Some(cmd) = protect.commands.iter().find(|c| matches_cmd(info, c)) {
+        return Some(format!("command {}", cmd));
+    }
+    if let Some(kind) = protect
+        .kinds
+        .iter()
+        .find(|k| ProcessKind::from_name(k) == info.kind)
+    {
+        return Some(format!("kind {}", kind));
+    }
+    if let Some(name) = info
+        .container
+        .as_ref()
+        .and_then(|c| protected_container(protect, c))
+    {
+        return Some(format!("container {}", name));
+    }
+    if protect.users.contains(&info.user) {
+        return Some(format!("user {}", info.user));
+    }
+    None
+}
+
+// The `protect.containers` entry naming the container or its compose project
+fn protected_container<'a>(protect: &'a ProtectConfig, c: &ContainerInfo) -> Option<&'a String> {
+    protect
+        .containers
+        .iter()
+        .find(|n| **n == c.name || c.compose_project.as_ref() == Some(*n))
+}
+
+// Same rules for containers that are stopped without looking at their listeners (`stack`)
+fn container_protection_reason(protect: &ProtectConfig, c: &ContainerInfo) -> Option<String> {
+    if let Some(port) = c
+        .ports
+        .iter()
+        .find(|p| protect.ports.contains(&p.host_port))
+    {
+        return Some(format!("port {}", port.host_port));
+    }
+    protected_container(protect, c).map(|name| format!("container {}", name))
+}
+
+// Captures listeners with the configured backend and classification rules, and marks the
+// ones covered by `[kill] protect`.
+pub fn capture_snapshot(
+    config: &Config,
+    sys: &System,
+    measure_cpu: bool,
+) -> Result<SystemSnapshot> {
+    let classifier =
+        Classifier::new(&config.classify.rules).context("Invalid [[classify.rules]] in config")?;
+    let mut snapshot = SystemSnapshot::capture_with(
+        config.backend.strategy.backend().as_ref(),
+        &classifier,
+        sys,
+        measure_cpu,
+    )?;
+    for info in snapshot.processes_by_port.values_mut().flatten() {
+        info.protected = protection_reason(&config.kill.protect, info);
+    }
+    Ok(snapshot)
+}
+
+pub fn scan_ports(snapshot: &SystemSnapshot, filter: &ScanFilter) -> Result<Vec<ProcessInfo>> {
     let mut final_infos = Vec::new();
 
     // Iterate over the range and check if we have info in the snapshot
@@ -18,13 +253,12 @@ pub fn scan_ports(snapshot: &SystemSnapshot, from: u16, to: u16) -> Result<Vec<P
 
     // Let's iterate snapshot keys for efficiency
     for (&port, infos) in &snapshot.processes_by_port {
-        if port >= from && port <= to {
-            final_infos.extend(infos.clone());
+        if port >= filter.from && port <= filter.to {
+            final_infos.extend(infos.iter().filter(|i| filter.matches(i)).cloned());
         }
     }
 
-    // Sort by port
-    final_infos.sort_by_key(|i| i.port);
+    sort_infos(&mut final_infos, SortKey::Port);
     Ok(final_infos)
 }
 
@@ -37,61 +271,434 @@ pub fn suggest_port(_snapshot: &SystemSnapshot, base: u16, max: u16) -> Result<u
     anyhow::bail!("No free ports found in range {}-{}", base, max);
 }
 
+// Refuses to touch processes owned by someone else unless the caller opted in, so the user
+// gets an explanation instead of a bare EPERM from kill(2).
+pub fn check_ownership(info: &ProcessInfo, all_users: bool) -> Result<()> {
+    let subject = format!("PID {} ({}) on port {}", info.pid, info.cmd, info.port);
+    check_owner(&subject, Some(&info.user), info.uid, all_users)
+}
+
+// Refuses to touch listeners covered by `[kill] protect` (the shared database, the VPN agent...)
+// unless the caller explicitly overrides it.
+pub fn check_protection(info: &ProcessInfo, override_protection: bool) -> Result<()> {
+    match &info.protected {
+        Some(reason) if !override_protection => anyhow::bail!(
+            "PID {} ({}) on port {} is protected ({}); use --override-protection to kill it anyway",
+            info.pid,
+            info.cmd,
+            info.port,
+            reason
+        ),
+        _ => Ok(()),
+    }
+}
+
+pub fn check_container_protection(
+    protect: &ProtectConfig,
+    c: &ContainerInfo,
+    override_protection: bool,
+) -> Result<()> {
+    match container_protection_reason(protect, c) {
+        Some(reason) if !override_protection => anyhow::bail!(
+            "{} is protected ({}); use --override-protection to stop it anyway",
+            container_subject(c),
+            reason
+        ),
+        _ => Ok(()),
+    }
+}
+
+#[allow(unused_variables)]
+fn check_owner(
+    subject: &str,
+    owner: Option<&str>,
+    uid: Option<u32>,
+    all_users: bool,
+) -> Result<()> {
+    if all_users {
+        return Ok(());
+    }
+
+    #[cfg(unix)]
+    {
+        let current_uid = nix::unistd::getuid().as_raw();
+        match uid {
+            Some(uid) if uid == current_uid => {}
+            Some(uid) => anyhow::bail!(
+                "{} is owned by {} (uid {}), not the current user (uid {}); use --all-users to kill it anyway",
+                subject,
+                owner.unwrap_or("another user"),
+                uid,
+                current_uid
+            ),
+            None => anyhow::bail!(
+                "Cannot determine the owner of {}; use --all-users to kill it anyway",
+                subject
+            ),
+        }
+    }
+
+    Ok(())
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum KillTarget {
+    Process(u32),
+    Group(u32),
+}
+
+impl KillTarget {
+    // kill(2) addresses a whole process group with a negative pid
+    #[cfg(unix)]
+    fn raw_pid(&self) -> i32 {
+        match self {
+            KillTarget::Process(pid) => *pid as i32,
+            KillTarget::Group(pgid) => -(*pgid as i32),
+        }
+    }
+}
+
+impl std::fmt::Display for KillTarget {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            KillTarget::Process(pid) => write!(f, "PID {}", pid),
+            KillTarget::Group(pgid) => write!(f, "process group {}", pgid),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum TargetScope {
+    // The process holding the socket
+    #[default]
+    Listener,
+    // The wrapper that launched it (npm run dev, cargo watch...), so it is not respawned
+    Parent,
+    // The listener's whole process group
+    Tree,
+}
+
+pub fn resolve_kill_target(
+    info: &ProcessInfo,
+    scope: TargetScope,
+    all_users: bool,
+) -> Result<KillTarget> {
+    match scope {
+        TargetScope::Listener => {
+            check_ownership(info, all_users)?;
+            Ok(KillTarget::Process(info.pid))
+        }
+        TargetScope::Parent => {
+            let parent = match info.supervisor() {
+                Some(parent) => parent,
+                None => {
+                    let launcher = info
+                        .ancestors
+                        .first()
+                        .map(|a| format!("{} (PID {})", a.cmd, a.pid))
+                        .unwrap_or_else(|| "init".to_string());
+                    anyhow::bail!(
+                        "PID {} ({}) was launched directly by {}; there is no supervising parent to stop",
+                        info.pid,
+                        info.cmd,
+                        launcher
+                    );
+                }
+            };
+            let subject = format!(
+                "PID {} ({}), parent of PID {}",
+                parent.pid, parent.display_cmd, info.pid
+            );
+            check_owner(&subject, None, parent.uid, all_users)?;
+            Ok(KillTarget::Process(parent.pid))
+        }
+        TargetScope::Tree => {
+            check_ownership(info, all_users)?;
+            let pgid = match info.pgid {
+                Some(pgid) if pgid > 1 => pgid,
+                _ => anyhow::bail!("Cannot determine the process group of PID {}", info.pid),
+            };
+            #[cfg(unix)]
+            if pgid == unsafe { libc::getpgrp() } as u32 {
+                anyhow::bail!(
+                    "PID {} shares crossport's own process group {}; refusing to signal it",
+                    info.pid,
+                    pgid
+                );
+            }
+            Ok(KillTarget::Group(pgid))
+        }
+    }
+}
+
+// Accepts any signal of the platform by name, with or without the SIG prefix and in any case
+// ("hup", "SIGUSR1"), or by number ("9")
+#[cfg(unix)]
+pub fn parse_signal(name: &str) -> Result<Signal> {
+    let name = name.trim();
+    if let Ok(number) = name.parse::<i32>() {
+        return Signal::try_from(number).map_err(|_| {
+            anyhow::anyhow!("Unknown signal number: {} (see --list-signals)", number)
+        });
+    }
+    let upper = name.to_uppercase();
+    let full = if upper.starts_with("SIG") {
+        upper
+    } else {
+        format!("SIG{}", upper)
+    };
+    Signal::from_str(&full)
+        .map_err(|_| anyhow::anyhow!("Unknown signal: {} (see --list-signals)", name))
+}
+
+// taskkill has no signals to choose from; --signal is accepted and ignored
+#[cfg(not(unix))]
+pub fn parse_signal(_name: &str) -> Result<()> {
+    Ok(())
+}
+
+// (number, name) of every signal parse_signal accepts
+pub fn signal_list() -> Vec<(i32, &'static str)> {
+    #[cfg(unix)]
+    {
+        Signal::iterator().map(|s| (s as i32, s.as_str())).collect()
+    }
+    #[cfg(not(unix))]
+    {
+        Vec::new()
+    }
+}
+
+// What kill_process waits for: the port being released, not just the process exiting, so that
+// `crossport kill 3000 && npm run dev` cannot race into EADDRINUSE
+pub struct PortWatch<'a> {
+    pub backend: &'a dyn PortBackend,
+    pub port: u16,
+    pub protocol: Protocol,
+    // The listener's bind address: another process on the same port but a different address
+    // (0.0.0.0 and [::]) is a separate listener and does not keep this one from being done
+    pub addr: IpAddr,
+}
+
+impl PortWatch<'_> {
+    // PIDs still listening on the watched socket address; None when the backend cannot tell
+    fn holders(&self) -> Option<Vec<u32>> {
+        let listeners = self.backend.listening_ports().ok()?;
+        let mut pids: Vec<u32> = listeners
+            .iter()
+            .filter(|l| l.port == self.port && l.protocol == self.protocol && l.addr == self.addr)
+            .map(|l| l.pid)
+            .collect();
+        // One row per address family / socket, not necessarily adjacent
+        pids.sort_unstable();
+        pids.dedup();
+        Some(pids)
+    }
+}
+
+#[cfg(unix)]
+const POLL_START: Duration = Duration::from_millis(20);
+#[cfg(unix)]
+const POLL_MAX: Duration = Duration::from_millis(500);
+// How long --force waits for SIGKILL to take effect
+#[cfg(unix)]
+const FORCE_TIMEOUT: Duration = Duration::from_secs(2);
+
+// Without a port to watch (or a backend that answers), the target exiting is what counts
+#[cfg(unix)]
+fn kill_done(raw_pid: i32, watch: Option<&PortWatch>) -> bool {
+    match watch.and_then(|w| w.holders()) {
+        Some(holders) => holders.is_empty(),
+        None => !is_alive(raw_pid),
+    }
+}
+
+#[cfg(unix)]
+fn is_alive(raw_pid: i32) -> bool {
+    // EPERM still means the process exists
+    signal::kill(Pid::from_raw(raw_pid), None) != Err(nix::errno::Errno::ESRCH)
+}
+
+// Polls with exponential backoff until kill_done or the timeout
+#[cfg(unix)]
+fn wait_until_done(raw_pid: i32, watch: Option<&PortWatch>, timeout: Duration) -> bool {
+    let deadline = Instant::now() + timeout;
+    let mut delay = POLL_START;
+    loop {
+        if kill_done(raw_pid, watch) {
+            return true;
+        }
+        let now = Instant::now();
+        if now >= deadline {
+            return false;
+        }
+        std::thread::sleep(delay.min(deadline - now));
+        delay = (delay * 2).min(POLL_MAX);
+    }
+}
+
+// One signal of the ladder and how long it was given to free the port
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct KillStep {
+    pub signal: String,
+    pub waited_ms: u64,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(tag = "outcome")]
+pub enum KillOutcome {
+    // --dry-run; `action` is what would have been done
+    DryRun {
+        action: String,
+    },
+    // An explicit --signal was delivered; what the process does with it is up to it
+    Signalled {
+        signal: String,
+    },
+    // The last of `steps` did it; `freed_port` is set when the port was watched
+    Stopped {
+        steps: Vec<KillStep>,
+        freed_port: Option<u16>,
+    },
+    AlreadyExited,
+    // The ladder ran out; `holders` still listen on the port
+    StillRunning {
+        steps: Vec<KillStep>,
+        port: Option<u16>,
+        holders: Vec<u32>,
+    },
+    // Gone before the first signal, but another process (a respawn?) holds the port
+    Respawned {
+        port: u16,
+        holders: Vec<u32>,
+    },
+    // taskkill has no ladder to report on
+    #[cfg(windows)]
+    Terminated,
+    Container {
+        action: ContainerAction,
+    },
+    Service {
+        command: String,
+    },
+    // Declined at the prompt, or a system process without --all-users
+    Skipped {
+        reason: String,
+    },
+    Refused {
+        reason: String,
+    },
+}
+
+impl KillOutcome {
+    // The port is not known to be free afterwards
+    pub fn failed(&self) -> bool {
+        matches!(
+            self,
+            KillOutcome::StillRunning { .. }
+                | KillOutcome::Respawned { .. }
+                | KillOutcome::Refused { .. }
+        )
+    }
+}
+
+// One `crossport kill --json` record per listener
+#[derive(Debug, Clone, Serialize)]
+pub struct KillRecord {
+    pub port: u16,
+    pub protocol: Protocol,
+    pub pid: u32,
+    pub cmd: String,
+    // What was acted on: "PID 123", "process group 120", "container web (docker 1a2b3c4d5e6f)"...
+    pub target: String,
+    #[serde(flatten)]
+    pub outcome: KillOutcome,
+    // How the listener was started (processes only), so it can be run again
+    pub launch: Option<LaunchSpec>,
+}
+
 pub fn kill_process(
-    pid: u32,
+    target: KillTarget,
     _signal_name: Option<&str>,
     force: bool,
     dry_run: bool,
-) -> Result<()> {
+    ladder: &[EscalationStep],
+    watch: Option<&PortWatch>,
+) -> Result<KillOutcome> {
     if dry_run {
-        println!("Would kill PID {}", pid);
-        return Ok(());
+        return Ok(KillOutcome::DryRun {
+            action: format!("kill {}", target),
+        });
     }
 
     // Unix-specific signal handling
     #[cfg(unix)]
     {
-        let nix_pid = Pid::from_raw(pid as i32);
+        let raw_pid = target.raw_pid();
+        let nix_pid = Pid::from_raw(raw_pid);
 
-        if force {
-            signal::kill(nix_pid, Signal::SIGKILL).context("Failed to send SIGKILL")?;
-            println!("Sent SIGKILL to {}", pid);
-            return Ok(());
+        // An explicit signal (HUP to reload, USR1 to dump...) is sent once, without waiting
+        if let Some(sig_name) = _signal_name.filter(|_| !force) {
+            let sig = parse_signal(sig_name)?;
+            signal::kill(nix_pid, sig).context("Failed to send signal")?;
+            return Ok(KillOutcome::Signalled {
+                signal: sig.as_str().to_string(),
+            });
         }
 
-        if let Some(sig_name) = _signal_name {
-            let sig = match sig_name.to_uppercase().as_str() {
-                "INT" => Signal::SIGINT,
-                "TERM" => Signal::SIGTERM,
-                "KILL" => Signal::SIGKILL,
-                _ => anyhow::bail!("Unknown signal: {}", sig_name),
-            };
-            signal::kill(nix_pid, sig).context("Failed to send signal")?;
-            println!("Sent {} to {}", sig_name, pid);
-            return Ok(());
-        }
-
-        // Default gentle strategy
-        if signal::kill(nix_pid, Signal::SIGINT).is_ok() {
-            std::thread::sleep(std::time::Duration::from_secs(1));
-
-            if unsafe { libc::kill(pid as i32, 0) } == 0 {
-                if signal::kill(nix_pid, Signal::SIGTERM).is_ok() {
-                    std::thread::sleep(std::time::Duration::from_secs(1));
-                    if unsafe { libc::kill(pid as i32, 0) } == 0 {
-                        signal::kill(nix_pid, Signal::SIGKILL)
-                            .context("Failed to send SIGKILL (final attempt)")?;
-                        println!("Process {} did not exit, sent SIGKILL", pid);
-                    } else {
-                        println!("Process {} exited after SIGTERM", pid);
-                    }
+        let ladder = if force {
+            vec![(Signal::SIGKILL, FORCE_TIMEOUT)]
+        } else {
+            ladder
+                .iter()
+                .map(|step| {
+                    Ok((
+                        parse_signal(&step.signal)?,
+                        Duration::from_millis(step.timeout_ms),
+                    ))
+                })
+                .collect::<Result<Vec<_>>>()?
+        };
+
+        let mut steps = Vec::new();
+        for (sig, timeout) in ladder {
+            match signal::kill(nix_pid, sig) {
+                Ok(()) => {}
+                // Gone already (possibly replaced by a respawned listener)
+                Err(nix::errno::Errno::ESRCH) => break,
+                Err(e) => {
+                    return Err(e).with_context(|| format!("Failed to send {}", sig.as_str()))
                 }
-            } else {
-                println!("Process {} exited after SIGINT", pid);
             }
-        } else {
-            println!("Process {} not found or already exited", pid);
+            let started = Instant::now();
+            let done = wait_until_done(raw_pid, watch, timeout);
+            steps.push(KillStep {
+                signal: sig.as_str().to_string(),
+                waited_ms: started.elapsed().as_millis() as u64,
+            });
+            if done {
+                return Ok(KillOutcome::Stopped {
+                    steps,
+                    freed_port: watch.map(|w| w.port),
+                });
+            }
+        }
+
+        let holders = watch.and_then(|w| w.holders()).unwrap_or_default();
+        if !steps.is_empty() {
+            return Ok(KillOutcome::StillRunning {
+                steps,
+                port: watch.map(|w| w.port),
+                holders,
+            });
         }
+        Ok(match watch {
+            Some(w) if !kill_done(raw_pid, watch) => KillOutcome::Respawned {
+                port: w.port,
+                holders,
+            },
+            _ => KillOutcome::AlreadyExited,
+        })
     }
 
     // Windows handling (basic)
@@ -104,21 +711,139 @@ pub fn kill_process(
         // We can use Command("taskkill").
 
         let mut cmd = Command::new("taskkill");
-        cmd.arg("/PID").arg(pid.to_string());
+        match target {
+            KillTarget::Process(pid) => cmd.arg("/PID").arg(pid.to_string()),
+            // No process groups on Windows; /T takes the child tree instead
+            KillTarget::Group(pid) => cmd.arg("/T").arg("/PID").arg(pid.to_string()),
+        };
 
         if force {
             cmd.arg("/F");
         }
+        // taskkill has no ladder to climb
+        let _ = (ladder, watch);
 
         let output = cmd.output().context("Failed to execute taskkill")?;
 
         if output.status.success() {
-            println!("Killed process {}", pid);
+            Ok(KillOutcome::Terminated)
         } else {
             let stderr = String::from_utf8_lossy(&output.stderr);
-            anyhow::bail!("Failed to kill process {}: {}", pid, stderr);
+            anyhow::bail!("Failed to kill {}: {}", target, stderr);
         }
     }
+}
 
-    Ok(())
+// "container web (docker 1a2b3c4d5e6f)"
+pub fn container_subject(container: &ContainerInfo) -> String {
+    format!(
+        "container {} ({} {})",
+        container.name,
+        container.runtime.as_str(),
+        container.short_id()
+    )
+}
+
+// How `crossport kill` stops a listener
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum KillRoute<'a> {
+    // Through its container runtime; the listener is only a proxy
+    Container,
+    // Through systemd / launchd, which would otherwise restart the killed process
+    Service,
+    // kill_process with this signal (None: the ladder)
+    Process(Option<&'a str>),
+}
+
+// Only an explicit --signal (HUP to reload...) goes to the process itself instead of its
+// container runtime or service manager. `[kill] default_signal` merely replaces the ladder.
+pub fn kill_route<'a>(
+    info: &ProcessInfo,
+    signal: Option<&'a str>,
+    kill: &'a KillConfig,
+    force: bool,
+    container_action: bool,
+) -> KillRoute<'a> {
+    let signalling = signal.is_some() && !force;
+    if info.container.is_some() && (container_action || !signalling) {
+        KillRoute::Container
+    } else if info.service.is_some() && !signalling {
+        KillRoute::Service
+    } else {
+        KillRoute::Process(signal.or(kill.default_signal.as_deref()))
+    }
+}
+
+pub fn stop_container(
+    container: &ContainerInfo,
+    action: ContainerAction,
+    dry_run: bool,
+) -> Result<KillOutcome> {
+    if dry_run {
+        return Ok(KillOutcome::DryRun {
+            action: format!(
+                "{} {}",
+                action.verb().to_lowercase(),
+                container_subject(container)
+            ),
+        });
+    }
+    container.apply(action)?;
+    Ok(KillOutcome::Container { action })
+}
+
+// "systemd unit postgresql.service"
+pub fn service_subject(unit: &ServiceUnit) -> String {
+    format!("{} unit {}", unit.manager_name(), unit.name)
+}
+
+// Stops the unit instead of killing its process, which the service manager would restart
+pub fn stop_service(unit: &ServiceUnit, dry_run: bool) -> Result<KillOutcome> {
+    let command = unit.stop_command().join(" ");
+    if dry_run {
+        return Ok(KillOutcome::DryRun {
+            action: format!("run: {}", command),
+        });
+    }
+    unit.stop()?;
+    Ok(KillOutcome::Service { command })
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, clap::ValueEnum)]
+pub enum StackAction {
+    // Remove every container of the project, freeing all of its ports
+    Down,
+    Restart,
+    // List the host ports the project publishes
+    Ports,
+}
+
+// Containers of a compose project, by service name
+pub fn stack_containers(project: &str) -> Result<Vec<ContainerInfo>> {
+    let containers = containers::list_containers();
+    let mut members: Vec<ContainerInfo> = containers
+        .iter()
+        .filter(|c| c.compose_project.as_deref() == Some(project))
+        .cloned()
+        .collect();
+    if members.is_empty() {
+        let mut known: Vec<&str> = containers
+            .iter()
+            .filter_map(|c| c.compose_project.as_deref())
+            .collect();
+        known.sort();
+        known.dedup();
+        if known.is_empty() {
+            anyhow::bail!("No running compose project named '{}'", project);
+        }
+        anyhow::bail!(
+            "No running compose project named '{}' (running: {})",
+            project,
+            known.join(", ")
+        );
+    }
+    members.sort_by(|a, b| {
+        (a.compose_service.as_deref(), &a.name).cmp(&(b.compose_service.as_deref(), &b.name))
+    });
+    Ok(members)
 }
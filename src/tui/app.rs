@@ -1,10 +1,17 @@
+use crate::config::Config;
+use crate::core::containers::{ContainerAction, ContainerInfo};
 use crate::core::{ProcessInfo, SystemSnapshot};
+use crate::ops::{KillOutcome, KillTarget};
 use anyhow::Result;
 use ratatui::widgets::TableState;
+use std::sync::mpsc::{self, Receiver, TryRecvError};
+use sysinfo::System;
 
 pub enum InputMode {
     Normal,
     ConfirmKill(u32),
+    // The selected port is published by a container; pick what to do with it
+    ContainerAction(Box<ContainerInfo>),
 }
 
 use std::time::{Duration, Instant};
@@ -15,16 +22,31 @@ pub struct App {
     pub snapshot: SystemSnapshot,
     pub input_mode: InputMode,
     pub last_refresh: Instant,
+    pub config: Config,
+    pub status: Option<String>,
+    pub wide: bool,
+    // Kept between refreshes so that CPU usage is measured over the refresh interval
+    sys: System,
+    // The kill ladder can take several seconds; it runs on a worker thread meanwhile
+    pending_kill: Option<PendingKill>,
+}
+
+struct PendingKill {
+    target: KillTarget,
+    started: Instant,
+    result: Receiver<Result<KillOutcome>>,
 }
 
 impl App {
-    pub fn new() -> Result<Self> {
-        let snapshot = SystemSnapshot::capture()?;
+    pub fn new(config: Config) -> Result<Self> {
+        let sys = SystemSnapshot::new_system(true);
+        let snapshot = crate::ops::capture_snapshot(&config, &sys, true)?;
         let mut processes = Vec::new();
         for infos in snapshot.processes_by_port.values() {
             processes.extend(infos.clone());
         }
-        processes.sort_by_key(|p| p.port);
+        crate::ops::sort_infos(&mut processes, crate::ops::SortKey::Port);
+        crate::ops::group_by_stack(&mut processes);
 
         let mut state = TableState::default();
         state.select(Some(0));
@@ -35,6 +57,11 @@ impl App {
             snapshot,
             input_mode: InputMode::Normal,
             last_refresh: Instant::now(),
+            config,
+            status: None,
+            wide: false,
+            sys,
+            pending_kill: None,
         })
     }
 
@@ -67,19 +94,95 @@ impl App {
     }
 
     pub fn kill_selected(&mut self) {
+        if let Some(pending) = &self.pending_kill {
+            self.status = Some(format!("Still stopping {}", pending.target));
+            return;
+        }
         if let Some(index) = self.state.selected() {
             if let Some(proc) = self.processes.get(index) {
-                self.input_mode = InputMode::ConfirmKill(proc.pid);
+                // No override from the TUI; use `crossport kill --override-protection`
+                if let Err(e) = crate::ops::check_protection(proc, false) {
+                    self.status = Some(e.to_string());
+                    return;
+                }
+                if let Some(container) = &proc.container {
+                    self.input_mode = InputMode::ContainerAction(Box::new(container.clone()));
+                    return;
+                }
+                // The TUI never kills other users' processes; use `crossport kill --all-users`
+                match crate::ops::check_ownership(proc, false) {
+                    Ok(()) => self.input_mode = InputMode::ConfirmKill(proc.pid),
+                    Err(e) => self.status = Some(e.to_string()),
+                }
             }
         }
     }
 
     pub fn confirm_kill(&mut self) -> Result<()> {
         if let InputMode::ConfirmKill(pid) = self.input_mode {
-            crate::ops::kill_process(pid, None, false, false)?;
-            self.refresh(true)?; // Force refresh after kill
+            self.input_mode = InputMode::Normal;
+            let target = KillTarget::Process(pid);
+            let strategy = self.config.backend.strategy;
+            let ladder = self.config.kill.ladder.clone();
+            let socket = self
+                .processes
+                .iter()
+                .find(|p| p.pid == pid)
+                .map(|p| (p.port, p.protocol, p.bind_addr));
+            let (tx, rx) = mpsc::channel();
+            std::thread::spawn(move || {
+                let backend = strategy.backend();
+                let watch = socket.map(|(port, protocol, addr)| crate::ops::PortWatch {
+                    backend: backend.as_ref(),
+                    port,
+                    protocol,
+                    addr,
+                });
+                let outcome =
+                    crate::ops::kill_process(target, None, false, false, &ladder, watch.as_ref());
+                let _ = tx.send(outcome);
+            });
+            self.status = Some(format!("Stopping {}...", target));
+            self.pending_kill = Some(PendingKill {
+                target,
+                started: Instant::now(),
+                result: rx,
+            });
+        }
+        Ok(())
+    }
+
+    // Reports the kill running in the background once it is over
+    fn poll_kill(&mut self) {
+        let Some(pending) = &self.pending_kill else {
+            return;
+        };
+        let subject = pending.target.to_string();
+        self.status = match pending.result.try_recv() {
+            Err(TryRecvError::Empty) => {
+                let elapsed = pending.started.elapsed().as_secs();
+                self.status = Some(format!("Stopping {}... {}s", subject, elapsed));
+                return;
+            }
+            Ok(Ok(outcome)) => Some(crate::display::kill_outcome_message(&subject, &outcome)),
+            Ok(Err(e)) => Some(format!("Kill failed: {}", e)),
+            Err(TryRecvError::Disconnected) => Some(format!("Kill of {} aborted", subject)),
+        };
+        self.pending_kill = None;
+        let _ = self.refresh(true); // Force refresh after kill
+    }
+
+    pub fn confirm_container_action(&mut self, action: ContainerAction) -> Result<()> {
+        let mode = std::mem::replace(&mut self.input_mode, InputMode::Normal);
+        if let InputMode::ContainerAction(container) = mode {
+            container.apply(action)?;
+            self.status = Some(format!(
+                "{} container {}",
+                action.past_tense(),
+                container.name
+            ));
+            self.refresh(true)?;
         }
-        self.input_mode = InputMode::Normal;
         Ok(())
     }
 
@@ -98,12 +201,14 @@ impl App {
             .selected()
             .and_then(|i| self.processes.get(i).map(|p| p.pid));
 
-        let snapshot = SystemSnapshot::capture()?;
+        self.sys.refresh_processes();
+        let snapshot = crate::ops::capture_snapshot(&self.config, &self.sys, true)?;
         let mut processes = Vec::new();
         for infos in snapshot.processes_by_port.values() {
             processes.extend(infos.clone());
         }
-        processes.sort_by_key(|p| p.port);
+        crate::ops::sort_infos(&mut processes, crate::ops::SortKey::Port);
+        crate::ops::group_by_stack(&mut processes);
         self.processes = processes;
         self.snapshot = snapshot;
         self.last_refresh = Instant::now();
@@ -126,6 +231,7 @@ impl App {
     }
 
     pub fn on_tick(&mut self) {
+        self.poll_kill();
         let _ = self.refresh(false);
     }
 }
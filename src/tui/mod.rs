@@ -1,6 +1,8 @@
 pub mod app;
 pub mod ui;
 
+use crate::config::Config;
+use crate::core::containers::ContainerAction;
 use crate::tui::app::InputMode;
 use anyhow::Result;
 use crossterm::{
@@ -11,14 +13,14 @@ use crossterm::{
 use ratatui::{backend::CrosstermBackend, Terminal};
 use std::io;
 
-pub fn run_tui() -> Result<()> {
+pub fn run_tui(config: Config) -> Result<()> {
     enable_raw_mode()?;
     let mut stdout = io::stdout();
     execute!(stdout, EnterAlternateScreen, EnableMouseCapture)?;
     let backend = CrosstermBackend::new(stdout);
     let mut terminal = Terminal::new(backend)?;
 
-    let mut app = app::App::new()?;
+    let mut app = app::App::new(config)?;
     let res = run_app(&mut terminal, &mut app);
 
     disable_raw_mode()?;
@@ -48,23 +50,42 @@ fn run_app<B: ratatui::backend::Backend>(
                 match app.input_mode {
                     InputMode::Normal => match key.code {
                         KeyCode::Char('q') => return Ok(()),
+                        KeyCode::Esc => app.status = None,
                         KeyCode::Char('j') | KeyCode::Down => app.next(),
                         KeyCode::Char('k') | KeyCode::Up => app.previous(),
                         KeyCode::Char('x') => app.kill_selected(),
+                        KeyCode::Char('w') => app.wide = !app.wide,
                         _ => {}
                     },
                     InputMode::ConfirmKill(_) => match key.code {
                         KeyCode::Char('y') => {
-                            if let Err(_e) = app.confirm_kill() {
-                                // TODO: Show error in UI? For now just print to stderr or ignore?
-                                // Since we are in raw mode, printing is bad.
-                                // Ideally we should have an error state in App.
-                                // For MVP, let's just ignore or maybe flash?
+                            if let Err(e) = app.confirm_kill() {
+                                // Printing is not an option in raw mode; surface it in the UI
+                                app.status = Some(format!("Kill failed: {}", e));
                             }
                         }
                         KeyCode::Char('n') | KeyCode::Esc => app.cancel_kill(),
                         _ => {}
                     },
+                    InputMode::ContainerAction(_) => {
+                        let action = match key.code {
+                            KeyCode::Char('s') => Some(ContainerAction::Stop),
+                            KeyCode::Char('r') => Some(ContainerAction::Restart),
+                            KeyCode::Char('k') => Some(ContainerAction::Kill),
+                            KeyCode::Char('d') => Some(ContainerAction::Rm),
+                            KeyCode::Char('n') | KeyCode::Esc => {
+                                app.cancel_kill();
+                                None
+                            }
+                            _ => None,
+                        };
+                        if let Some(action) = action {
+                            if let Err(e) = app.confirm_container_action(action) {
+                                app.status =
+                                    Some(format!("{} container failed: {}", action.verb(), e));
+                            }
+                        }
+                    }
                 }
             }
         }
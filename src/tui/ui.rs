@@ -1,3 +1,4 @@
+use crate::display::{format_bytes, format_duration, project_column};
 use crate::tui::app::{App, InputMode};
 use ratatui::{
     layout::{Constraint, Layout, Rect},
@@ -8,13 +9,34 @@ use ratatui::{
 
 pub fn ui(f: &mut Frame, app: &mut App) {
     let rects = Layout::default()
-        .constraints([Constraint::Percentage(100)].as_ref())
+        .constraints([Constraint::Min(1), Constraint::Length(1)].as_ref())
         .margin(1)
         .split(f.size());
 
     let selected_style = Style::default().add_modifier(Modifier::REVERSED);
     let normal_style = Style::default().bg(Color::Blue);
-    let header_cells = ["PORT", "PID", "USER", "CMD", "KIND", "PROJ"]
+    let mut headers = vec![
+        "PORT", "PROTO", "PID", "USER", "CMD", "KIND", "UP", "CPU%", "MEM",
+    ];
+    let mut widths = vec![
+        Constraint::Length(8),
+        Constraint::Length(5),
+        Constraint::Length(8),
+        Constraint::Length(10),
+        Constraint::Length(20),
+        Constraint::Length(10),
+        Constraint::Length(8),
+        Constraint::Length(6),
+        Constraint::Length(10),
+    ];
+    if app.wide {
+        headers.push("COMMAND");
+        widths.push(Constraint::Length(32));
+    }
+    headers.push("PROJ");
+    widths.push(Constraint::Min(10));
+
+    let header_cells = headers
         .iter()
         .map(|h| ratatui::widgets::Cell::from(*h).style(Style::default().fg(Color::Red)));
     let header = Row::new(header_cells)
@@ -22,49 +44,74 @@ pub fn ui(f: &mut Frame, app: &mut App) {
         .height(1)
         .bottom_margin(0);
 
-    let rows = app.processes.iter().map(|item| {
-        let proj = if let Some(container) = &item.container_name {
-            container.clone()
-        } else {
-            item.project_root
-                .as_ref()
-                .and_then(|p| p.file_name())
-                .map(|n| n.to_string_lossy().to_string())
-                .unwrap_or_default()
+    // Stack members are grouped together (ops::group_by_stack); label each group once, like
+    // `scan` does. Labels are not selectable, so the selection is shifted past them.
+    let mut rows = Vec::new();
+    let mut selected_row = None;
+    let mut current_stack = None;
+    for (index, item) in app.processes.iter().enumerate() {
+        if let Some(stack) = item.stack().filter(|s| Some(*s) != current_stack) {
+            let mut cells = vec![String::new(); headers.len()];
+            cells[0] = "stack".to_string();
+            // Under PROJ, where its members show "stack/service"
+            if let Some(last) = cells.last_mut() {
+                *last = stack.to_string();
+            }
+            rows.push(Row::new(cells).style(Style::default().add_modifier(Modifier::BOLD)));
+        }
+        current_stack = item.stack();
+        if app.state.selected() == Some(index) {
+            selected_row = Some(rows.len());
+        }
+
+        let proj = project_column(item);
+
+        // Covered by [kill] protect
+        let port = match item.protected {
+            Some(_) => format!("{} 🔒", item.port),
+            None => item.port.to_string(),
         };
 
-        let cells = vec![
-            item.port.to_string(),
+        let mut cells = vec![
+            port,
+            item.protocol.as_str().to_string(),
             item.pid.to_string(),
             item.user.clone(),
             item.cmd.clone(),
             item.kind.as_str().to_string(),
-            proj.to_string(),
+            item.uptime().map(format_duration).unwrap_or_default(),
+            item.cpu_percent
+                .map(|cpu| format!("{:.1}", cpu))
+                .unwrap_or_default(),
+            format_bytes(item.rss_bytes),
         ];
-        Row::new(cells).height(1).bottom_margin(0)
-    });
+        if app.wide {
+            cells.push(item.display_cmd.clone());
+        }
+        cells.push(proj.to_string());
+        rows.push(Row::new(cells).height(1).bottom_margin(0));
+    }
+
+    let t = Table::new(rows, widths)
+        .header(header)
+        .block(
+            Block::default()
+                .borders(Borders::ALL)
+                .title("Crossport TUI (q: Quit, x: Kill, w: Wide)"),
+        )
+        .highlight_style(selected_style)
+        .highlight_symbol(">> ");
 
-    let t = Table::new(
-        rows,
-        [
-            Constraint::Length(6),
-            Constraint::Length(8),
-            Constraint::Length(10),
-            Constraint::Length(20),
-            Constraint::Length(10),
-            Constraint::Min(10),
-        ],
-    )
-    .header(header)
-    .block(
-        Block::default()
-            .borders(Borders::ALL)
-            .title("Crossport TUI (q: Quit, x: Kill)"),
-    )
-    .highlight_style(selected_style)
-    .highlight_symbol(">> ");
+    let mut state = app.state.clone();
+    state.select(selected_row);
+    f.render_stateful_widget(t, rects[0], &mut state);
+    // The scroll offset counts label rows too; keep it for the next frame
+    *app.state.offset_mut() = state.offset();
 
-    f.render_stateful_widget(t, rects[0], &mut app.state);
+    if let Some(status) = &app.status {
+        let text = Paragraph::new(status.as_str()).style(Style::default().fg(Color::Yellow));
+        f.render_widget(text, rects[1]);
+    }
 
     if let InputMode::ConfirmKill(pid) = app.input_mode {
         let block = Block::default().title("Confirm Kill").borders(Borders::ALL);
@@ -78,6 +125,21 @@ pub fn ui(f: &mut Frame, app: &mut App) {
         f.render_widget(Clear, area); // Clear background
         f.render_widget(text, area);
     }
+
+    if let InputMode::ContainerAction(container) = &app.input_mode {
+        let block = Block::default().title("Container").borders(Borders::ALL);
+        let area = centered_rect(60, 20, f.size());
+        let text = Paragraph::new(format!(
+            "Port is published by {} container {} ({})\n[s]top  [r]estart  [k]ill  [d]elete  [Esc] cancel",
+            container.runtime.as_str(),
+            container.name,
+            container.image
+        ))
+        .block(block)
+        .style(Style::default().fg(Color::Red).add_modifier(Modifier::BOLD));
+        f.render_widget(Clear, area);
+        f.render_widget(text, area);
+    }
 }
 
 fn centered_rect(percent_x: u16, percent_y: u16, r: Rect) -> Rect {
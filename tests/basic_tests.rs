@@ -29,9 +29,51 @@ fn test_env_backup_naming() {
     }
 }
 
+fn sample_info() -> crossport::core::ProcessInfo {
+    use crossport::core::process::{AddressFamily, ProcessInfo, ProcessKind, Protocol};
+    use std::net::{IpAddr, Ipv4Addr};
+
+    ProcessInfo {
+        pid: 12345,
+        user: "testuser".to_string(),
+        uid: None,
+        cmd: "node".to_string(),
+        args: vec![
+            "node".to_string(),
+            "/test/project/node_modules/.bin/next".to_string(),
+            "dev".to_string(),
+        ],
+        exe: Some(PathBuf::from("/usr/bin/node")),
+        display_cmd: "next dev".to_string(),
+        cwd: PathBuf::from("/test"),
+        started_at: Some(1_700_000_000),
+        cpu_percent: Some(1.5),
+        rss_bytes: 64 * 1024 * 1024,
+        ppid: None,
+        pgid: None,
+        ancestors: Vec::new(),
+        project_root: Some(PathBuf::from("/test/project")),
+        package_root: Some(PathBuf::from("/test/project")),
+        package_name: None,
+        git: None,
+        container_name: None,
+        container: None,
+        port_forward: None,
+        tunnel: None,
+        service: None,
+        protected: None,
+        kind: ProcessKind::Dev,
+        port: 3000,
+        protocol: Protocol::Tcp,
+        bind_addr: IpAddr::V4(Ipv4Addr::LOCALHOST),
+        family: AddressFamily::Ipv4,
+    }
+}
+
 #[test]
 fn test_json_serialization() {
-    use crossport::core::process::{ProcessInfo, ProcessKind};
+    use crossport::core::process::{AddressFamily, ProcessInfo, ProcessKind, Protocol};
+    use std::net::{IpAddr, Ipv4Addr};
     use std::path::PathBuf;
 
     let info = ProcessInfo {
@@ -39,15 +81,1096 @@ fn test_json_serialization() {
         user: "testuser".to_string(),
         uid: None,
         cmd: "node".to_string(),
+        args: Vec::new(),
+        exe: None,
+        display_cmd: "node".to_string(),
         cwd: PathBuf::from("/test"),
+        started_at: None,
+        cpu_percent: None,
+        rss_bytes: 0,
+        ppid: None,
+        pgid: None,
+        ancestors: Vec::new(),
         project_root: Some(PathBuf::from("/test/project")),
+        package_root: None,
+        package_name: None,
+        git: None,
         container_name: None,
+        container: None,
+        port_forward: None,
+        tunnel: None,
+        service: None,
+        protected: None,
         kind: ProcessKind::Dev,
         port: 3000,
+        protocol: Protocol::Tcp,
+        bind_addr: IpAddr::V4(Ipv4Addr::LOCALHOST),
+        family: AddressFamily::Ipv4,
     };
 
     let json = serde_json::to_string(&info).unwrap();
     assert!(json.contains("\"pid\":12345"));
     assert!(json.contains("\"port\":3000"));
     assert!(json.contains("\"kind\":\"Dev\""));
+    assert!(json.contains("\"protocol\":\"Tcp\""));
+    assert!(json.contains("\"bind_addr\":\"127.0.0.1\""));
+}
+
+#[test]
+fn test_parse_proc_net() {
+    use crossport::core::procfs::parse_proc_net;
+    use crossport::core::Protocol;
+
+    let tcp = "  sl  local_address rem_address   st tx_queue rx_queue tr tm->when retrnsmt   uid  timeout inode
+   0: 0100007F:0BB8 00000000:0000 0A 00000000:00000000 00:00000000 00000000  1000        0 41234 1 0000000000000000 100 0 0 10 0
+   1: 0100007F:0BB8 0100007F:D431 01 00000000:00000000 00:00000000 00000000  1000        0 41299 1 0000000000000000 20 4 30 10 -1
+   2: 00000000:1538 00000000:0000 0A 00000000:00000000 00:00000000 00000000   999        0 0 1 0000000000000000 100 0 0 10 0
+";
+    // Only LISTEN rows with a real inode are reported
+    assert_eq!(
+        parse_proc_net(tcp, Protocol::Tcp),
+        vec![("127.0.0.1".parse().unwrap(), 3000, 41234)]
+    );
+
+    let udp = "   sl  local_address rem_address   st tx_queue rx_queue tr tm->when retrnsmt   uid  timeout inode ref pointer drops
+ 3377: 0100007F:14E9 00000000:0000 07 00000000:00000000 00:00000000 00000000     0        0 10277 2 0000000000000000 0
+ 3673: 0100007F:E611 0100007F:14E9 01 00000000:00000000 00:00000000 00000000     0        0 10280 2 0000000000000000 0
+";
+    // Connected UDP sockets are clients and are ignored
+    assert_eq!(
+        parse_proc_net(udp, Protocol::Udp),
+        vec![("127.0.0.1".parse().unwrap(), 5353, 10277)]
+    );
+
+    let tcp6 = "  sl  local_address                         remote_address                        st tx_queue rx_queue tr tm->when retrnsmt   uid  timeout inode
+   0: 00000000000000000000000001000000:0BB9 00000000000000000000000000000000:0000 0A 00000000:00000000 00:00000000 00000000     0        0 20001 1 0000000000000000 100 0 0 10 0
+   1: 00000000000000000000000000000000:0050 00000000000000000000000000000000:0000 0A 00000000:00000000 00:00000000 00000000     0        0 20002 1 0000000000000000 100 0 0 10 0
+";
+    assert_eq!(
+        parse_proc_net(tcp6, Protocol::Tcp),
+        vec![
+            ("::1".parse().unwrap(), 3001, 20001),
+            ("::".parse().unwrap(), 80, 20002)
+        ]
+    );
+}
+
+#[test]
+fn test_parse_backend_outputs() {
+    use crossport::core::backend::{parse_lsof, parse_netstat, parse_ss, Listener};
+    use crossport::core::Protocol::{Tcp, Udp};
+
+    let l = |pid, port, protocol, addr: &str| Listener {
+        pid,
+        port,
+        protocol,
+        addr: addr.parse().unwrap(),
+    };
+
+    let lsof = "p812\nf3\ntIPv6\nPTCP\nn*:22\np4242\ntIPv4\nPUDP\nn127.0.0.1:5353\nPUDP\nn127.0.0.1:58897->127.0.0.1:53\n";
+    assert_eq!(
+        parse_lsof(lsof),
+        vec![l(812, 22, Tcp, "::"), l(4242, 5353, Udp, "127.0.0.1")]
+    );
+
+    let ss = "Netid State  Recv-Q Send-Q Local Address:Port Peer Address:Port Process
+tcp   LISTEN 0      511    127.0.0.1:3000     0.0.0.0:*     users:((\"node\",pid=4242,fd=21))
+tcp   LISTEN 0      128    [::]:80            [::]:*        users:((\"nginx\",pid=900,fd=6),(\"nginx\",pid=901,fd=6))
+udp   UNCONN 0      0      127.0.0.53%lo:53   0.0.0.0:*     users:((\"systemd-resolve\",pid=610,fd=13))
+";
+    assert_eq!(
+        parse_ss(ss),
+        vec![
+            l(4242, 3000, Tcp, "127.0.0.1"),
+            l(900, 80, Tcp, "::"),
+            l(901, 80, Tcp, "::"),
+            l(610, 53, Udp, "127.0.0.53")
+        ]
+    );
+
+    let netstat = "  TCP    0.0.0.0:8088           0.0.0.0:0              LISTENING       31715
+  UDP    0.0.0.0:5353           *:*                                    4120
+tcp6       0      0 :::22                   :::*                    LISTEN      812/sshd
+udp        0      0 0.0.0.0:68              0.0.0.0:*                           640/dhclient
+tcp4       0      0  *.5432                 *.*                    LISTEN      131072  131072    777      0 0x0100
+";
+    assert_eq!(
+        parse_netstat(netstat),
+        vec![
+            l(31715, 8088, Tcp, "0.0.0.0"),
+            l(4120, 5353, Udp, "0.0.0.0"),
+            l(812, 22, Tcp, "::"),
+            l(640, 68, Udp, "0.0.0.0"),
+            l(777, 5432, Tcp, "0.0.0.0")
+        ]
+    );
+}
+
+#[test]
+fn test_addr_filter() {
+    use crossport::core::backend::parse_socket_addr;
+    use crossport::ops::AddrFilter;
+    use std::net::IpAddr;
+
+    let wildcard: IpAddr = "0.0.0.0".parse().unwrap();
+    let addr = |s: &str| parse_socket_addr(s, wildcard).unwrap().0;
+
+    assert_eq!(
+        addr("[::ffff:127.0.0.1]:80"),
+        "127.0.0.1".parse::<IpAddr>().unwrap()
+    );
+    assert_eq!(
+        addr("[fe80::1%eth0]:123"),
+        "fe80::1".parse::<IpAddr>().unwrap()
+    );
+    assert_eq!(
+        addr("127.0.0.1.5432"),
+        "127.0.0.1".parse::<IpAddr>().unwrap()
+    );
+
+    let loopback: AddrFilter = "loopback".parse().unwrap();
+    assert!(loopback.matches(&addr("[::1]:3000")));
+    assert!(!loopback.matches(&addr("*:3000")));
+
+    let any: AddrFilter = "any".parse().unwrap();
+    assert!(any.matches(&addr("*:3000")));
+    assert!(any.matches(&addr(":::3000")));
+
+    let exact: AddrFilter = "192.168.1.10".parse().unwrap();
+    assert!(exact.matches(&addr("192.168.1.10:8080")));
+    assert!("nonsense".parse::<AddrFilter>().is_err());
+}
+
+#[test]
+fn test_audit_policy() {
+    use crossport::audit::audit;
+    use crossport::config::AuditConfig;
+    use crossport::core::process::{Exposure, ProcessKind};
+
+    let loopback_dev = sample_info();
+    let mut exposed_dev = sample_info();
+    exposed_dev.port = 5173;
+    exposed_dev.bind_addr = "0.0.0.0".parse().unwrap();
+    let mut lan_other = sample_info();
+    lan_other.kind = ProcessKind::Other;
+    lan_other.bind_addr = "192.168.1.10".parse().unwrap();
+
+    let infos = vec![loopback_dev, exposed_dev, lan_other];
+    let report = audit(&infos, &AuditConfig::default());
+    let exposures: Vec<_> = report.findings.iter().map(|f| f.exposure).collect();
+    assert_eq!(
+        exposures,
+        vec![Exposure::Loopback, Exposure::AllInterfaces, Exposure::Lan]
+    );
+    // Only the dev server on 0.0.0.0 breaks the default policy; "other" is not policed
+    assert_eq!(report.violations(), 1);
+    assert!(report.findings[1].violation);
+    // Same spelling in --json output as in the config
+    let json = serde_json::to_value(&report).unwrap();
+    assert_eq!(json["findings"][1]["exposure"], "all");
+    assert_eq!(json["max_exposure"], "loopback");
+
+    let allowed = AuditConfig {
+        allow_ports: vec![5173],
+        ..Default::default()
+    };
+    assert_eq!(audit(&infos, &allowed).violations(), 0);
+
+    let policy: AuditConfig =
+        toml::from_str("kinds = [\"other\"]\nmax_exposure = \"all\"").unwrap();
+    assert_eq!(audit(&infos, &policy).violations(), 0);
+
+    // A public address is as exposed as a wildcard; mapped and link-local addresses are
+    // classified like their plain forms
+    let exposure = |addr: &str| Exposure::of(&addr.parse().unwrap());
+    assert_eq!(exposure("203.0.113.7"), Exposure::AllInterfaces);
+    assert_eq!(exposure("2001:db8::1"), Exposure::AllInterfaces);
+    assert_eq!(exposure("::ffff:127.0.0.1"), Exposure::Loopback);
+    assert_eq!(exposure("::ffff:10.0.0.5"), Exposure::Lan);
+    assert_eq!(exposure("169.254.1.1"), Exposure::Lan);
+    assert_eq!(exposure("fd00::1"), Exposure::Lan);
+    assert_eq!(exposure("fe80::1"), Exposure::Lan);
+    let mut public_other = sample_info();
+    public_other.kind = ProcessKind::Other;
+    public_other.bind_addr = "203.0.113.7".parse().unwrap();
+    let lan_policy: AuditConfig =
+        toml::from_str("kinds = [\"other\"]\nmax_exposure = \"lan\"").unwrap();
+    assert_eq!(audit(&[public_other], &lan_policy).violations(), 1);
+}
+
+#[cfg(unix)]
+#[test]
+fn test_check_ownership() {
+    use crossport::ops::check_ownership;
+
+    let current = nix::unistd::getuid().as_raw();
+    let mut info = sample_info();
+    info.uid = Some(current);
+    assert!(check_ownership(&info, false).is_ok());
+
+    info.uid = Some(current.wrapping_add(1));
+    info.user = "someone".to_string();
+    let err = check_ownership(&info, false).unwrap_err().to_string();
+    assert!(err.contains("PID 12345 (node) on port 3000 is owned by someone"));
+    assert!(err.contains("not the current user"));
+    assert!(err.contains("use --all-users"));
+    assert!(check_ownership(&info, true).is_ok());
+
+    // Unknown owner: refused too, unless overridden
+    info.uid = None;
+    assert!(check_ownership(&info, false)
+        .unwrap_err()
+        .to_string()
+        .contains("Cannot determine the owner"));
+    assert!(check_ownership(&info, true).is_ok());
+}
+
+#[test]
+fn test_display_command() {
+    use crossport::core::cmdline::display_command;
+
+    let argv = |s: &str| s.split_whitespace().map(String::from).collect::<Vec<_>>();
+
+    assert_eq!(
+        display_command("node", &argv("node /app/node_modules/.bin/next dev")),
+        "next dev"
+    );
+    assert_eq!(
+        display_command(
+            "node",
+            &argv("/usr/bin/node --inspect /app/node_modules/vite/bin/vite.js --port 5173")
+        ),
+        "vite --port 5173"
+    );
+    assert_eq!(
+        display_command(
+            "node",
+            &argv("node /app/node_modules/@nestjs/cli/bin/nest.js start")
+        ),
+        "nest start"
+    );
+    assert_eq!(
+        display_command("node", &argv("node -r dotenv/config server.js")),
+        "server.js"
+    );
+    assert_eq!(
+        display_command(
+            "python3.12",
+            &argv("python3.12 -m uvicorn app:api --reload")
+        ),
+        "uvicorn app:api --reload"
+    );
+    assert_eq!(
+        display_command(
+            "postgres",
+            &argv("/usr/lib/postgresql/16/bin/postgres -D /var/lib/pg")
+        ),
+        "postgres -D pg"
+    );
+    assert_eq!(
+        display_command("python3", &argv("python3 -c import\nsocket")),
+        "python3 -c"
+    );
+    assert_eq!(display_command("redis-server", &[]), "redis-server");
+}
+
+#[test]
+fn test_sort_infos() {
+    use crossport::ops::{sort_infos, SortKey};
+
+    let mut old_light = sample_info();
+    old_light.port = 3000;
+    old_light.started_at = Some(1_000);
+    old_light.rss_bytes = 10;
+    old_light.cpu_percent = Some(0.5);
+    let mut new_heavy = sample_info();
+    new_heavy.port = 8080;
+    new_heavy.started_at = Some(2_000);
+    new_heavy.rss_bytes = 1_000;
+    new_heavy.cpu_percent = Some(50.0);
+
+    let ports =
+        |infos: &[crossport::core::ProcessInfo]| infos.iter().map(|i| i.port).collect::<Vec<_>>();
+    let mut infos = vec![new_heavy, old_light];
+
+    sort_infos(&mut infos, SortKey::Port);
+    assert_eq!(ports(&infos), vec![3000, 8080]);
+    sort_infos(&mut infos, SortKey::Mem);
+    assert_eq!(ports(&infos), vec![8080, 3000]);
+    sort_infos(&mut infos, SortKey::Uptime);
+    assert_eq!(ports(&infos), vec![3000, 8080]);
+    sort_infos(&mut infos, SortKey::Cpu);
+    assert_eq!(ports(&infos), vec![8080, 3000]);
+}
+
+#[test]
+fn test_supervisor_detection() {
+    use crossport::core::process::AncestorInfo;
+
+    let ancestor = |pid, cmd: &str, args: &str| AncestorInfo {
+        pid,
+        uid: None,
+        cmd: cmd.to_string(),
+        args: args.split_whitespace().map(String::from).collect(),
+        display_cmd: args.to_string(),
+    };
+
+    // zsh -> npm run dev -> sh -c next dev -> node (listener)
+    let mut info = sample_info();
+    info.ancestors = vec![
+        ancestor(300, "sh", "sh -c next dev"),
+        ancestor(200, "npm", "npm run dev"),
+        ancestor(100, "-zsh", "-zsh"),
+        ancestor(50, "tmux: server", "tmux"),
+    ];
+    assert_eq!(info.supervisor().map(|a| a.pid), Some(200));
+
+    // Started straight from an interactive shell: nothing to escalate to
+    info.ancestors = vec![ancestor(100, "bash", "bash")];
+    assert!(info.supervisor().is_none());
+}
+
+#[test]
+fn test_classification_rules() {
+    use crossport::core::classify::{Classifier, ClassifyContext, RuleConfig};
+    use std::path::Path;
+
+    let config: crossport::config::Config = toml::from_str(
+        r#"
+        [[classify.rules]]
+        kind = "frontend"
+        cmd = "^(node|bun)$"
+        ports = "3000-3999"
+
+        [[classify.rules]]
+        kind = "dev"
+        cwd = "/home/*/src/**"
+        "#,
+    )
+    .unwrap();
+    let classifier = Classifier::new(&config.classify.rules).unwrap();
+
+    let args = vec!["node".to_string(), "server.js".to_string()];
+    let ctx = |cmd, cwd, port, has_container| ClassifyContext {
+        cmd,
+        args: &args,
+        exe: None,
+        cwd: Path::new(cwd),
+        user: "alice",
+        port,
+        has_container,
+    };
+
+    let frontend = classifier.classify(&ctx("node", "/tmp", 3000, false));
+    assert_eq!(frontend, ProcessKind::Custom("frontend".to_string()));
+    assert_eq!(frontend.as_str(), "frontend");
+    // Port outside the rule's range falls through to the built-ins
+    assert_eq!(
+        classifier.classify(&ctx("node", "/tmp", 8080, false)),
+        ProcessKind::Other
+    );
+    assert_eq!(
+        classifier.classify(&ctx("ruby", "/home/alice/src/app", 8080, false)),
+        ProcessKind::Dev
+    );
+    // `*` does not cross path segments
+    assert_eq!(
+        classifier.classify(&ctx("ruby", "/home/alice/x/src/app", 8080, false)),
+        ProcessKind::Other
+    );
+
+    // Built-in defaults are kept
+    let builtin = Classifier::new(&[]).unwrap();
+    assert_eq!(
+        builtin.classify(&ctx("node", "/Users/me/_workspace/app", 3000, false)),
+        ProcessKind::Dev
+    );
+    assert_eq!(
+        builtin.classify(&ctx("node", "/opt/homebrew/var", 3000, true)),
+        ProcessKind::Docker
+    );
+    assert_eq!(
+        builtin.classify(&ctx("redis-server", "/opt/homebrew/var", 6379, false)),
+        ProcessKind::Brew
+    );
+
+    let bad = RuleConfig {
+        kind: "x".to_string(),
+        cmd: Some("(".to_string()),
+        ..Default::default()
+    };
+    assert!(Classifier::new(&[bad]).is_err());
+}
+
+#[test]
+fn test_project_detection() {
+    use crossport::core::project;
+    use std::fs;
+
+    let base = std::env::temp_dir().join(format!("crossport-project-{}", std::process::id()));
+    let write = |rel: &str, content: &str| {
+        let path = base.join(rel);
+        fs::create_dir_all(path.parent().unwrap()).unwrap();
+        fs::write(path, content).unwrap();
+    };
+
+    // git monorepo with an npm workspace package
+    fs::create_dir_all(base.join("mono/.git")).unwrap();
+    write(
+        "mono/package.json",
+        r#"{"private": true, "workspaces": ["packages/*"]}"#,
+    );
+    write("mono/packages/web/package.json", r#"{"name": "@acme/web"}"#);
+    fs::create_dir_all(base.join("mono/packages/web/src")).unwrap();
+
+    let p = project::detect(&base.join("mono/packages/web/src"));
+    assert_eq!(p.root, Some(base.join("mono")));
+    assert_eq!(p.package_root, Some(base.join("mono/packages/web")));
+    assert_eq!(p.package_name.as_deref(), Some("@acme/web"));
+
+    let mut info = sample_info();
+    info.project_root = p.root;
+    info.package_root = p.package_root;
+    info.package_name = p.package_name;
+    assert_eq!(info.project_label().as_deref(), Some("mono/@acme/web"));
+
+    // Linked worktrees are named after their repository, with the commit they have checked out
+    info.project_root = Some(base.join("app-feature"));
+    info.package_root = Some(base.join("app-feature"));
+    info.package_name = None;
+    info.git = Some(crossport::core::git::GitInfo {
+        branch: None,
+        head: Some("fedcba9876543210fedcba9876543210fedcba98".to_string()),
+        worktree: base.join("app-feature"),
+        repo: base.join("app"),
+        dirty: Some(false),
+    });
+    assert_eq!(info.project_label().as_deref(), Some("app"));
+    assert_eq!(crossport::display::project_column(&info), "app@fedcba9");
+
+    // Cargo virtual workspace without git: the workspace is the root
+    write("rs/Cargo.toml", "[workspace]\nmembers = [\"api\"]\n");
+    write("rs/api/Cargo.toml", "[package]\nname = \"api-server\"\n");
+    let p = project::detect(&base.join("rs/api"));
+    assert_eq!(p.root, Some(base.join("rs")));
+    assert_eq!(p.package_name.as_deref(), Some("api-server"));
+
+    // Standalone go module and pyproject
+    write("svc/go.mod", "module github.com/acme/billing\n\ngo 1.22\n");
+    let p = project::detect(&base.join("svc"));
+    assert_eq!(p.root, Some(base.join("svc")));
+    assert_eq!(p.package_name.as_deref(), Some("billing"));
+
+    write("py/pyproject.toml", "[tool.poetry]\nname = \"worker\"\n");
+    assert_eq!(
+        project::detect(&base.join("py")).package_name.as_deref(),
+        Some("worker")
+    );
+
+    // crossport.toml marks the root even inside a larger tree
+    write(
+        "mono/tools/crossport.toml",
+        "[project]\nname = \"tooling\"\n",
+    );
+    let p = project::detect(&base.join("mono/tools"));
+    assert_eq!(p.root, Some(base.join("mono/tools")));
+    assert_eq!(p.package_name.as_deref(), Some("tooling"));
+
+    fs::remove_dir_all(&base).unwrap();
+}
+
+#[test]
+fn test_rootless_forwarder_classification() {
+    use crossport::core::classify::{Classifier, ClassifyContext};
+    use std::path::Path;
+
+    // Rootless forwarders count as containers even when no runtime could be queried
+    let classifier = Classifier::new(&[]).unwrap();
+    for cmd in ["rootlessport", "pasta", "slirp4netns"] {
+        let kind = classifier.classify(&ClassifyContext {
+            cmd,
+            args: &[],
+            exe: None,
+            cwd: Path::new("/"),
+            user: "alice",
+            port: 8080,
+            has_container: false,
+        });
+        assert_eq!(kind, ProcessKind::Docker, "{}", cmd);
+    }
+}
+
+#[test]
+fn test_group_by_stack() {
+    use crossport::core::containers::{ContainerInfo, ContainerRuntime};
+    use crossport::ops::group_by_stack;
+
+    let in_stack = |port: u16, project: &str, service: &str| {
+        let mut info = sample_info();
+        info.port = port;
+        info.container = Some(ContainerInfo {
+            id: format!("{}-{}", project, service),
+            runtime: ContainerRuntime::Docker,
+            endpoint: None,
+            name: format!("{}-{}-1", project, service),
+            image: "img".to_string(),
+            state: "running".to_string(),
+            status: "Up".to_string(),
+            health: None,
+            compose_project: Some(project.to_string()),
+            compose_service: Some(service.to_string()),
+            ports: Vec::new(),
+        });
+        info
+    };
+    let standalone = |port: u16| {
+        let mut info = sample_info();
+        info.port = port;
+        info
+    };
+
+    let mut infos = vec![
+        in_stack(3000, "shop", "web"),
+        standalone(3001),
+        in_stack(5432, "blog", "db"),
+        in_stack(6379, "shop", "cache"),
+        standalone(8080),
+    ];
+    group_by_stack(&mut infos);
+    let order: Vec<(u16, Option<&str>)> = infos.iter().map(|i| (i.port, i.stack())).collect();
+    assert_eq!(
+        order,
+        vec![
+            (3001, None),
+            (8080, None),
+            (5432, Some("blog")),
+            (3000, Some("shop")),
+            (6379, Some("shop")),
+        ]
+    );
+    assert_eq!(crossport::display::project_column(&infos[3]), "shop/web");
+}
+
+#[test]
+fn test_port_forward_classification() {
+    use crossport::core::classify::{Classifier, ClassifyContext};
+    use std::path::Path;
+
+    let argv = |s: &str| s.split_whitespace().map(String::from).collect::<Vec<_>>();
+
+    // Classified as k8s however kubectl was launched
+    let classifier = Classifier::new(&[]).unwrap();
+    let kind_of = |cmd: &str, args: &[String]| {
+        classifier.classify(&ClassifyContext {
+            cmd,
+            args,
+            exe: None,
+            cwd: Path::new("/"),
+            user: "alice",
+            port: 8080,
+            has_container: false,
+        })
+    };
+    assert_eq!(
+        kind_of(
+            "kubectl",
+            &argv("/usr/local/bin/kubectl -n shop port-forward svc/api 8080:80")
+        ),
+        ProcessKind::Kubernetes
+    );
+    assert_eq!(
+        kind_of(
+            "kubectl.1.29",
+            &argv("/opt/k8s/kubectl port-forward svc/api 8080")
+        ),
+        ProcessKind::Kubernetes
+    );
+    assert_eq!(
+        kind_of(
+            "microk8s.kubectl",
+            &argv("/snap/bin/microk8s.kubectl port-forward -n ingress svc/nginx 8443:443")
+        ),
+        ProcessKind::Kubernetes
+    );
+    assert_eq!(kind_of("k9s", &argv("k9s")), ProcessKind::Kubernetes);
+    assert_eq!(
+        kind_of("kubectl", &argv("kubectl proxy")),
+        ProcessKind::Other
+    );
+}
+
+#[test]
+fn test_ssh_tunnel_classification() {
+    use crossport::core::classify::{Classifier, ClassifyContext};
+    use std::path::Path;
+
+    let argv = |s: &str| s.split_whitespace().map(String::from).collect::<Vec<_>>();
+
+    let classifier = Classifier::new(&[]).unwrap();
+    let kind_of = |cmd: &str, args: &[String]| {
+        classifier.classify(&ClassifyContext {
+            cmd,
+            args,
+            exe: None,
+            cwd: Path::new("/home/alice/_workspace/app"),
+            user: "alice",
+            port: 5432,
+            has_container: false,
+        })
+    };
+    assert_eq!(
+        kind_of(
+            "ssh",
+            &argv("ssh -fNL 5432:db.internal:5432 deploy@bastion")
+        ),
+        ProcessKind::Tunnel
+    );
+    assert_eq!(
+        kind_of("python3", &argv("python3 /usr/bin/sshuttle -r bastion 0/0")),
+        ProcessKind::Tunnel
+    );
+    assert_eq!(kind_of("ssh", &argv("ssh -l root host")), ProcessKind::Dev);
+}
+
+#[cfg(unix)]
+#[test]
+fn test_kill_route() {
+    use crossport::config::KillConfig;
+    use crossport::core::containers::{ContainerInfo, ContainerRuntime};
+    use crossport::core::service::parse_cgroup;
+    use crossport::ops::{kill_route, KillRoute};
+
+    // As in the README's example config
+    let kill: KillConfig = toml::from_str("default_signal = \"SIGTERM\"").unwrap();
+    let mut info = sample_info();
+    assert_eq!(
+        kill_route(&info, None, &kill, false, false),
+        KillRoute::Process(Some("SIGTERM"))
+    );
+
+    // default_signal does not bypass the service manager; only an explicit --signal does
+    info.service = parse_cgroup("0::/system.slice/postgresql.service\n");
+    assert_eq!(
+        kill_route(&info, None, &kill, false, false),
+        KillRoute::Service
+    );
+    assert_eq!(
+        kill_route(&info, None, &kill, true, false),
+        KillRoute::Service
+    );
+    assert_eq!(
+        kill_route(&info, Some("HUP"), &kill, false, false),
+        KillRoute::Process(Some("HUP"))
+    );
+
+    info.service = None;
+    info.container = Some(ContainerInfo {
+        id: "4f9a1c2b3d4e".to_string(),
+        runtime: ContainerRuntime::Docker,
+        endpoint: None,
+        name: "shop-db-1".to_string(),
+        image: "postgres:16".to_string(),
+        state: "running".to_string(),
+        status: "Up".to_string(),
+        health: None,
+        compose_project: None,
+        compose_service: None,
+        ports: Vec::new(),
+    });
+    assert_eq!(
+        kill_route(&info, None, &kill, false, false),
+        KillRoute::Container
+    );
+    assert_eq!(
+        kill_route(&info, Some("HUP"), &kill, false, false),
+        KillRoute::Process(Some("HUP"))
+    );
+    // --container picks the runtime action whatever the signal
+    assert_eq!(
+        kill_route(&info, Some("HUP"), &kill, false, true),
+        KillRoute::Container
+    );
+}
+
+#[cfg(unix)]
+#[test]
+fn test_signal_parsing() {
+    use crossport::config::Config;
+    use crossport::ops::{parse_signal, signal_list};
+    use nix::sys::signal::Signal;
+
+    for name in ["HUP", "hup", "SIGHUP", "sighup", "1", " HUP "] {
+        assert_eq!(parse_signal(name).unwrap(), Signal::SIGHUP, "{}", name);
+    }
+    assert_eq!(parse_signal("usr2").unwrap(), Signal::SIGUSR2);
+    assert_eq!(parse_signal("SIGQUIT").unwrap(), Signal::SIGQUIT);
+    assert_eq!(parse_signal("9").unwrap(), Signal::SIGKILL);
+    for bad in ["", "0", "999", "-1", "TERMINATE", "SIG"] {
+        assert!(parse_signal(bad).is_err(), "{}", bad);
+    }
+
+    // Everything listed is accepted back, by name and by number
+    let signals = signal_list();
+    assert!(signals.len() >= 20);
+    for (number, name) in signals {
+        assert_eq!(parse_signal(name).unwrap() as i32, number);
+        assert_eq!(parse_signal(&number.to_string()).unwrap().as_str(), name);
+    }
+
+    let config: Config = toml::from_str("[kill]\ndefault_signal = \"SIGTERM\"").unwrap();
+    assert!(config.validate().is_ok());
+    let config: Config = toml::from_str("[kill]\ndefault_signal = \"TERMINATE\"").unwrap();
+    assert!(config.validate().is_err());
+}
+
+#[cfg(unix)]
+#[test]
+fn test_kill_ladder() {
+    use crossport::config::Config;
+    use crossport::core::backend::{Listener, PortBackend};
+    use crossport::core::Protocol;
+    use crossport::ops::{kill_process, KillOutcome, KillTarget, PortWatch};
+    use std::process::Command;
+
+    // Reports the port as held by PID 1 forever, like a listener respawned by a supervisor
+    struct Occupied;
+    impl PortBackend for Occupied {
+        fn name(&self) -> &'static str {
+            "occupied"
+        }
+        fn listening_ports(&self) -> anyhow::Result<Vec<Listener>> {
+            Ok(vec![Listener {
+                pid: 1,
+                port: 3000,
+                protocol: Protocol::Tcp,
+                addr: "127.0.0.1".parse().unwrap(),
+            }])
+        }
+    }
+
+    // Reaped in the background so that the killed child does not linger as a zombie
+    let spawn = || {
+        let mut child = Command::new("sleep").arg("30").spawn().unwrap();
+        let pid = child.id();
+        std::thread::spawn(move || child.wait());
+        pid
+    };
+
+    let config = Config::default();
+    let signals: Vec<&str> = config
+        .kill
+        .ladder
+        .iter()
+        .map(|s| s.signal.as_str())
+        .collect();
+    assert_eq!(signals, vec!["INT", "TERM", "KILL"]);
+
+    let config: Config = toml::from_str(
+        "[kill]\nladder = [{ signal = \"TERM\", timeout_ms = 500 }, { signal = \"KILL\", timeout_ms = 500 }]",
+    )
+    .unwrap();
+    assert!(config.validate().is_ok());
+    let started = std::time::Instant::now();
+    let outcome = kill_process(
+        KillTarget::Process(spawn()),
+        None,
+        false,
+        false,
+        &config.kill.ladder,
+        None,
+    )
+    .unwrap();
+    // Polling returns as soon as the process is gone instead of sleeping out the timeout
+    assert!(started.elapsed() < std::time::Duration::from_millis(400));
+    match outcome {
+        KillOutcome::Stopped { steps, freed_port } => {
+            assert_eq!(steps.len(), 1);
+            assert_eq!(steps[0].signal, "SIGTERM");
+            assert_eq!(freed_port, None);
+        }
+        other => panic!("unexpected outcome: {:?}", other),
+    }
+
+    let watch = PortWatch {
+        backend: &Occupied,
+        port: 3000,
+        protocol: Protocol::Tcp,
+        addr: "127.0.0.1".parse().unwrap(),
+    };
+    let pid = spawn();
+    let outcome = kill_process(
+        KillTarget::Process(pid),
+        None,
+        false,
+        false,
+        &config.kill.ladder,
+        Some(&watch),
+    )
+    .unwrap();
+    assert!(outcome.failed());
+    match &outcome {
+        KillOutcome::StillRunning {
+            steps,
+            port,
+            holders,
+        } => {
+            // The target died on SIGTERM, so there was no one left to send SIGKILL to
+            let signals: Vec<&str> = steps.iter().map(|s| s.signal.as_str()).collect();
+            assert_eq!(signals, vec!["SIGTERM"]);
+            assert_eq!(*port, Some(3000));
+            assert_eq!(holders, &vec![1]);
+        }
+        other => panic!("unexpected outcome: {:?}", other),
+    }
+
+    // Another process listening on the same port but a different address is not the target
+    let other_addr = PortWatch {
+        addr: "::".parse().unwrap(),
+        ..watch
+    };
+    let outcome = kill_process(
+        KillTarget::Process(spawn()),
+        None,
+        false,
+        false,
+        &config.kill.ladder,
+        Some(&other_addr),
+    )
+    .unwrap();
+    match outcome {
+        KillOutcome::Stopped { steps, freed_port } => {
+            assert_eq!(steps.len(), 1);
+            assert_eq!(freed_port, Some(3000));
+        }
+        other => panic!("unexpected outcome: {:?}", other),
+    }
+
+    let config: Config = toml::from_str("[kill]\nladder = []").unwrap();
+    assert!(config.validate().is_err());
+    let config: Config =
+        toml::from_str("[kill]\nladder = [{ signal = \"NOPE\", timeout_ms = 1 }]").unwrap();
+    assert!(config.validate().is_err());
+}
+
+#[test]
+fn test_kill_outcome_reporting() {
+    use crossport::core::Protocol;
+    use crossport::display::kill_outcome_message;
+    use crossport::ops::{kill_process, KillOutcome, KillRecord, KillStep, KillTarget};
+
+    let outcome = kill_process(KillTarget::Group(4242), None, false, true, &[], None).unwrap();
+    assert_eq!(
+        outcome,
+        KillOutcome::DryRun {
+            action: "kill process group 4242".to_string()
+        }
+    );
+    assert!(!outcome.failed());
+    assert_eq!(
+        kill_outcome_message("process group 4242", &outcome),
+        "Would kill process group 4242"
+    );
+
+    let step = |signal: &str| KillStep {
+        signal: signal.to_string(),
+        waited_ms: 120,
+    };
+    let stopped = KillOutcome::Stopped {
+        steps: vec![step("SIGINT"), step("SIGTERM")],
+        freed_port: Some(3000),
+    };
+    assert_eq!(
+        kill_outcome_message("PID 7", &stopped),
+        "PID 7 stopped after SIGTERM; port 3000 is free"
+    );
+    let stuck = KillOutcome::StillRunning {
+        steps: vec![step("SIGKILL")],
+        port: Some(3000),
+        holders: vec![8, 9],
+    };
+    assert_eq!(
+        kill_outcome_message("PID 7", &stuck),
+        "Port 3000 is still in use by PID 8, 9 after SIGKILL to PID 7"
+    );
+
+    // --json: one flat record per listener, tagged with the outcome
+    let record = KillRecord {
+        port: 3000,
+        protocol: Protocol::Tcp,
+        pid: 7,
+        cmd: "node".to_string(),
+        target: "PID 7".to_string(),
+        outcome: stopped,
+        launch: None,
+    };
+    let json = serde_json::to_value(&record).unwrap();
+    assert_eq!(json["outcome"], "Stopped");
+    assert_eq!(json["target"], "PID 7");
+    assert_eq!(json["freed_port"], 3000);
+    assert_eq!(json["steps"][1]["signal"], "SIGTERM");
+    assert_eq!(json["steps"][1]["waited_ms"], 120);
+
+    let skipped = serde_json::to_value(KillOutcome::Skipped {
+        reason: "declined".to_string(),
+    })
+    .unwrap();
+    assert_eq!(
+        skipped,
+        serde_json::json!({"outcome": "Skipped", "reason": "declined"})
+    );
+}
+
+#[test]
+fn test_kill_filter() {
+    use crossport::ops::KillFilter;
+
+    let mut info = sample_info();
+    info.project_root = Some(PathBuf::from("/work/myapp"));
+    info.package_root = Some(PathBuf::from("/work/myapp/apps/web"));
+    info.package_name = Some("web".to_string());
+
+    assert!(KillFilter::default().is_empty());
+    assert!(KillFilter::default().matches(&info));
+
+    let filter = |kind: Option<&str>, project: Option<&str>, cmd: Option<&str>| KillFilter {
+        kind: kind.map(String::from),
+        project: project.map(String::from),
+        cmd: cmd.map(String::from),
+        user: None,
+    };
+    assert!(filter(Some("dev"), None, None).matches(&info));
+    assert!(filter(Some("DEV"), None, None).matches(&info));
+    assert!(!filter(Some("docker"), None, None).matches(&info));
+
+    // The repository, a package of it, or the full "repo/package" label
+    assert!(filter(None, Some("myapp"), None).matches(&info));
+    assert!(filter(None, Some("web"), None).matches(&info));
+    assert!(filter(None, Some("myapp/web"), None).matches(&info));
+    assert!(!filter(None, Some("other"), None).matches(&info));
+
+    assert!(filter(Some("dev"), Some("myapp"), Some("node")).matches(&info));
+    assert!(!filter(Some("dev"), Some("myapp"), Some("bun")).matches(&info));
+
+    let user = KillFilter {
+        user: Some("root".to_string()),
+        ..Default::default()
+    };
+    assert!(!user.is_empty());
+    assert!(!user.matches(&info));
+}
+
+#[test]
+fn test_kill_protection() {
+    use crossport::config::ProtectConfig;
+    use crossport::core::containers::{ContainerInfo, ContainerRuntime, PortMapping};
+    use crossport::core::process::Protocol;
+    use crossport::ops::{check_container_protection, check_protection, protection_reason};
+
+    let mut info = sample_info();
+    assert_eq!(protection_reason(&ProtectConfig::default(), &info), None);
+    assert!(check_protection(&info, false).is_ok());
+
+    let protect = |config: ProtectConfig, info: &_| protection_reason(&config, info);
+    assert_eq!(
+        protect(
+            ProtectConfig {
+                ports: vec![5432, 3000],
+                ..Default::default()
+            },
+            &info
+        ),
+        Some("port 3000".to_string())
+    );
+    // The executable file name counts as the command too
+    info.cmd = "next-server".to_string();
+    assert_eq!(
+        protect(
+            ProtectConfig {
+                commands: vec!["node".to_string()],
+                ..Default::default()
+            },
+            &info
+        ),
+        Some("command node".to_string())
+    );
+    assert_eq!(
+        protect(
+            ProtectConfig {
+                kinds: vec!["dev".to_string()],
+                users: vec!["testuser".to_string()],
+                ..Default::default()
+            },
+            &info
+        ),
+        Some("kind dev".to_string())
+    );
+    assert_eq!(
+        protect(
+            ProtectConfig {
+                users: vec!["testuser".to_string()],
+                containers: vec!["shared-db".to_string()],
+                ..Default::default()
+            },
+            &info
+        ),
+        Some("user testuser".to_string())
+    );
+
+    info.protected = Some("port 3000".to_string());
+    let err = check_protection(&info, false).unwrap_err().to_string();
+    assert!(err.contains("is protected (port 3000)"));
+    assert!(err.contains("--override-protection"));
+    assert!(check_protection(&info, true).is_ok());
+
+    // `stack down` checks the containers themselves
+    let container = ContainerInfo {
+        id: "0123456789abcdef".to_string(),
+        runtime: ContainerRuntime::Docker,
+        endpoint: None,
+        name: "shared-db-postgres-1".to_string(),
+        image: "postgres:16".to_string(),
+        state: "running".to_string(),
+        status: "Up".to_string(),
+        health: None,
+        compose_project: Some("shared-db".to_string()),
+        compose_service: Some("postgres".to_string()),
+        ports: vec![PortMapping {
+            host_ip: None,
+            host_port: 5432,
+            container_port: 5432,
+            protocol: Protocol::Tcp,
+        }],
+    };
+    let by_project = ProtectConfig {
+        containers: vec!["shared-db".to_string()],
+        ..Default::default()
+    };
+    let err = check_container_protection(&by_project, &container, false)
+        .unwrap_err()
+        .to_string();
+    assert!(err.contains("shared-db-postgres-1"));
+    assert!(err.contains("is protected (container shared-db)"));
+    assert!(check_container_protection(&by_project, &container, true).is_ok());
+    let by_port = ProtectConfig {
+        ports: vec![5432],
+        ..Default::default()
+    };
+    let err = check_container_protection(&by_port, &container, false)
+        .unwrap_err()
+        .to_string();
+    assert!(err.contains("(port 5432)"));
+    assert!(check_container_protection(&ProtectConfig::default(), &container, false).is_ok());
+}
+
+#[test]
+fn test_launch_spec() {
+    use crossport::core::launch::LaunchSpec;
+
+    // Without /proc/<pid> the snapshot's view of the process is used
+    let mut info = sample_info();
+    info.pid = u32::MAX;
+    let spec = LaunchSpec::capture(&info);
+    assert_eq!(spec.argv, info.args);
+    assert_eq!(spec.cwd, info.cwd);
+    assert_eq!(spec.env, None);
 }